@@ -1,6 +1,7 @@
 //! Qualify and delimit areas.
 
-use std::fmt::{Display, Formatter};
+use alloc::string::ToString;
+use core::fmt::{Display, Formatter};
 use crate::control::ControlSequence;
 use crate::escape::{escape, EscapeSequence};
 
@@ -38,7 +39,7 @@ pub enum Qualification {
 }
 
 impl Display for Qualification {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Qualification::UnprotectNoGuard => "0",
             Qualification::ProtectGuard => "1",
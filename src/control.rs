@@ -1,7 +1,16 @@
 //! This module defines the [ControlSequence] struct which represent sequence introduced by **CSI**.
+//!
+//! The [AnySequence] enum represents any control function that is not a control string, and the [AnsiElement]
+//! enum any element of a stream, text included.
+//!
+//! The [Sequence] struct batches control functions and text to write them at once.
 
-use std::fmt::{Display, Formatter};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use crate::escape::EscapeSequence;
 use crate::introducers::CSI;
+use crate::parser::{classify, csi_split, SequenceKind};
 
 /// A control sequence is a string of bit combinations starting with the control function CONTROL
 /// SEQUENCE INTRODUCER (CSI).
@@ -17,6 +26,7 @@ use crate::introducers::CSI;
 ///
 /// print!("{}", sequence); // Prints \x1b[1;1H
 /// // or
+/// # #[cfg(feature = "std")]
 /// sequence.exec(); // Prints \x1b[1;1H
 /// ```
 ///
@@ -27,31 +37,715 @@ use crate::introducers::CSI;
 /// use coded_chars::cursor::set_position;
 ///
 /// let sequence = set_position(1, 1); // Returns a ControlSequence
+/// # #[cfg(feature = "std")]
 /// sequence.exec(); // Prints \x1b[1;1H
 /// ```
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct ControlSequence {
     arguments: Vec<String>,
     end: String,
+    private: bool,
 }
 
 impl ControlSequence {
+    /// Creates a control sequence with the parameters `from` and the intermediate and final bytes `end`.
+    ///
+    /// Neither `from` nor `end` are checked, see [ControlSequence::try_new].
     pub fn new(from: &[&str], end: &str) -> Self {
-        ControlSequence { arguments: from.iter().map(|s| s.to_string()).collect::<Vec<_>>(), end: end.to_string() }
+        ControlSequence { arguments: from.iter().map(|s| s.to_string()).collect::<Vec<_>>(), end: end.to_string(), private: false }
+    }
+
+    /// Same as [ControlSequence::new], but checks that the sequence is well-formed.
+    ///
+    /// Each argument may only contain parameter bytes (`0x30` to `0x3F`) other than the separator `;`, and
+    /// `end` must be made of intermediate bytes (`0x20` to `0x2F`) followed by a single final byte (`0x40`
+    /// to `0x7E`).
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::control::{ControlSequence, SequenceError};
+    ///
+    /// assert_eq!(ControlSequence::try_new(&["1", "2"], "H").unwrap().to_string(), "\x1b[1;2H");
+    /// assert_eq!(ControlSequence::try_new(&["1;2"], "H").err(), Some(SequenceError::InvalidArgument(0)));
+    /// ```
+    pub fn try_new(from: &[&str], end: &str) -> Result<Self, SequenceError> {
+        if let Some(i) = from.iter().position(|argument| argument.bytes().any(|b| b == b';' || !(0x30..=0x3F).contains(&b))) {
+            return Err(SequenceError::InvalidArgument(i));
+        }
+        match end.as_bytes() {
+            [] => Err(SequenceError::EmptyEnd),
+            [intermediates @ .., 0x40..=0x7E] if intermediates.iter().all(|b| (0x20..=0x2F).contains(b)) => Ok(Self::new(from, end)),
+            _ => Err(SequenceError::InvalidEnd),
+        }
+    }
+
+    /// Creates a control sequence with private parameters, the arguments being prefixed by `?`.
+    ///
+    /// This form is used by the DEC private modes, like the alternate screen or the cursor visibility.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::control::ControlSequence;
+    ///
+    /// assert_eq!(ControlSequence::private(&["1049"], "h").to_string(), "\x1b[?1049h");
+    /// ```
+    pub fn private(from: &[&str], end: &str) -> Self {
+        ControlSequence { private: true, ..Self::new(from, end) }
+    }
+
+    /// Parses the control sequence at the start of `bytes`.
+    ///
+    /// The sequence may be introduced by **ESC** `[` or by the 8-bit CSI byte (`0x9B`). The parameters are
+    /// split on `;`, a missing parameter being kept as an empty argument, and the end is made of the
+    /// intermediate bytes followed by the final byte.
+    ///
+    /// Returns the sequence and the number of bytes it spans. [ParseError::Unterminated] is returned when
+    /// `bytes` ends before the final byte, even right after **ESC**, so more bytes may complete the sequence.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::control::ControlSequence;
+    ///
+    /// let (sequence, len) = ControlSequence::parse(b"\x1b[0 Hrest").unwrap();
+    /// assert_eq!(sequence.to_string(), "\x1b[0 H");
+    /// assert_eq!(len, 5);
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Result<(ControlSequence, usize), ParseError> {
+        let from = match bytes {
+            // The 8-bit CSI byte is not valid UTF-8, so it can't be classified.
+            [0x9B, ..] => 1,
+            _ => {
+                let head = &bytes[..bytes.len().min(2)];
+                let head = core::str::from_utf8(head)
+                    .or_else(|e| core::str::from_utf8(&head[..e.valid_up_to()]))
+                    .unwrap_or_default();
+                match classify(head) {
+                    Some((SequenceKind::Csi, from)) => from,
+                    Some((SequenceKind::Escape, 1)) if bytes.len() == 1 => return Err(ParseError::Unterminated),
+                    _ => return Err(ParseError::NotASequence),
+                }
+            }
+        };
+        let (parameters, end) = csi_split(&bytes[from..]).map_err(|at| match at {
+            Some(at) => ParseError::InvalidByte(from + at),
+            None => ParseError::Unterminated,
+        })?;
+        let final_at = from + parameters.len() + end.len() - 1;
+
+        // Both slices only hold ASCII bytes at this point.
+        let parameters = core::str::from_utf8(parameters).map_err(|_| ParseError::InvalidByte(from))?;
+        let end = core::str::from_utf8(end).map_err(|_| ParseError::InvalidByte(final_at))?;
+        let (private, parameters) = match parameters.strip_prefix('?') {
+            Some(parameters) => (true, parameters),
+            None => (false, parameters),
+        };
+        let arguments = if parameters.is_empty() { Vec::new() } else { parameters.split(';').map(String::from).collect() };
+        Ok((ControlSequence { arguments, end: end.to_string(), private }, final_at + 1))
+    }
+
+    /// Returns the printed sequence, same as `to_string` but naming the intent.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::cursor::set_position;
+    ///
+    /// let home: String = set_position(1, 1).into();
+    /// assert_eq!(home, set_position(1, 1).to_sequence_string());
+    /// ```
+    pub fn to_sequence_string(&self) -> String { self.to_string() }
+
+    /// Returns the length in bytes of the printed sequence, without formatting it.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::cursor::set_position;
+    ///
+    /// assert_eq!(set_position(12, 1).byte_len(), "\x1b[12;1H".len());
+    /// ```
+    pub fn byte_len(&self) -> usize {
+        let separators = self.arguments.len().saturating_sub(1);
+        let arguments = self.arguments.iter().map(String::len).sum::<usize>();
+        // CSI is printed in its 7-bit form, ESC [.
+        2 + usize::from(self.private) + arguments + separators + self.end.len()
+    }
+
+    /// Checks the selective parameters of this sequence against the values defined by ECMA-48 for the
+    /// control function identified by its end.
+    ///
+    /// Returns [SequenceError::OutOfRange] with the index of the first invalid parameter, the parameters of an
+    /// argument like `"38;5;208"` being counted separately. Empty parameters take their default value and
+    /// are valid. The numeric parameters, the private sequences and the functions not defined by ECMA-48
+    /// are not checked.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::control::{ControlSequence, SequenceError};
+    /// use coded_chars::presentation::select_graphic;
+    ///
+    /// assert_eq!(select_graphic().fg_color_256(208).bold().get().validate(), Ok(()));
+    /// assert_eq!(ControlSequence::new(&["9"], " I").validate(), Err(SequenceError::OutOfRange(0)));
+    /// ```
+    pub fn validate(&self) -> Result<(), SequenceError> {
+        if self.private {
+            return Ok(());
+        }
+        let parameters = self.arguments.iter().flat_map(|argument| argument.split(';')).collect::<Vec<_>>();
+        if self.end == "m" {
+            return validate_sgr(&parameters);
+        }
+        for (i, parameter) in parameters.iter().enumerate() {
+            let Some(max) = selective_max(&self.end, i) else { continue };
+            if !parameter.is_empty() && !parameter.parse::<u16>().is_ok_and(|n| n <= max) {
+                return Err(SequenceError::OutOfRange(i));
+            }
+        }
+        Ok(())
     }
 
     /// Prints the current sequence in `stdout` directly.
+    #[cfg(feature = "std")]
     pub fn exec(&self) {
         use std::io::stdout;
         use std::io::Write;
-        
-        print!("{}", self);
-        stdout().flush().unwrap()
+
+        let mut stdout = stdout();
+        self.write_to(&mut stdout).unwrap();
+        stdout.flush().unwrap()
+    }
+
+    /// Writes the current sequence to `w`, without flushing it.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::cursor::set_position;
+    ///
+    /// let mut buffer = vec![];
+    /// set_position(1, 1).write_to(&mut buffer).unwrap();
+    /// assert_eq!(buffer, b"\x1b[1;1H");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_to<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        write!(w, "{}", self)
+    }
+}
+
+/// Shows the sequence as `CSI[parameters]end`, the control characters being escaped.
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::{hide, set_position};
+///
+/// assert_eq!(format!("{:?}", set_position(1, 1)), "CSI[1;1]H");
+/// assert_eq!(format!("{:?}", hide()), "CSI[?25]l");
+/// ```
+impl Debug for ControlSequence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let prefix = if self.private { "?" } else { "" };
+        write!(f, "CSI[{}{}]{}", prefix, self.arguments.join(";").escape_debug(), self.end.escape_debug())
     }
 }
 
 impl Display for ControlSequence {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}{}", CSI, self.arguments.join(";"), self.end)
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let prefix = if self.private { "?" } else { "" };
+        write!(f, "{}{}{}{}", CSI, prefix, self.arguments.join(";"), self.end)
+    }
+}
+
+/// Queues a control sequence through the command API of crossterm, available with the `crossterm` feature.
+///
+/// ### Example
+/// ```
+/// # #[cfg(feature = "crossterm")]
+/// # {
+/// use std::io::stdout;
+/// use coded_chars::cursor::set_position;
+/// use crossterm::execute;
+///
+/// execute!(stdout(), set_position(1, 1)).unwrap();
+/// # }
+/// ```
+#[cfg(feature = "crossterm")]
+impl crossterm::Command for ControlSequence {
+    fn write_ansi(&self, f: &mut impl core::fmt::Write) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+
+    /// Control sequences have no WinAPI equivalent, they are only supported by terminals handling ANSI
+    /// sequences.
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Err(std::io::Error::other("control sequences have no WinAPI equivalent"))
+    }
+}
+
+impl From<ControlSequence> for String {
+    fn from(sequence: ControlSequence) -> Self { sequence.to_string() }
+}
+
+/// Decodes the numeric parameters of the control sequence at the start of `bytes`, as sent by a terminal in
+/// a reply.
+///
+/// The sequence must end with `final_byte` and its parameters must be prefixed by `?` if `private` is set.
+/// Each parameter is returned with its byte offset, an omitted parameter being `None`. No parameter is
+/// returned if the parameter string is empty.
+pub(crate) fn parse_numbers(bytes: &[u8], final_byte: u8, private: bool) -> Result<Vec<(usize, Option<usize>)>, ParseError> {
+    let (_, len) = ControlSequence::parse(bytes)?;
+    let mut offset = if bytes[0] == 0x9B { 1 } else { 2 };
+    if bytes[len - 1] != final_byte {
+        return Err(ParseError::InvalidByte(len - 1));
+    }
+    if private {
+        if bytes[offset] != b'?' {
+            return Err(ParseError::InvalidByte(offset));
+        }
+        offset += 1;
+    }
+    if offset == len - 1 {
+        return Ok(Vec::new());
+    }
+
+    let mut numbers = Vec::new();
+    for parameter in bytes[offset..len - 1].split(|b| *b == b';') {
+        let number = parameter.iter().enumerate().try_fold(None, |n: Option<usize>, (i, b)| match b {
+            b'0'..=b'9' => n.unwrap_or(0).checked_mul(10)
+                .and_then(|n| n.checked_add(usize::from(b - b'0')))
+                .map(Some)
+                .ok_or(ParseError::InvalidByte(offset + i)),
+            _ => Err(ParseError::InvalidByte(offset + i)),
+        })?;
+        numbers.push((offset, number));
+        offset += parameter.len() + 1;
+    }
+    Ok(numbers)
+}
+
+/// Returns the greatest value defined by ECMA-48 for the `index`-th parameter of the control function
+/// ending with `end`, if this parameter is selective.
+fn selective_max(end: &str, index: usize) -> Option<u16> {
+    Some(match (end, index) {
+        // SM, RM
+        ("h" | "l", _) => 22,
+        // ED, EL, EF, EA, SDS, SRS, SPQR, PEC
+        ("J" | "K" | "N" | "O" | "]" | "[" | " X" | " Z", _) => 2,
+        // TBC
+        ("g", _) => 5,
+        // CTC
+        ("W", _) => 6,
+        // DSR
+        ("n", _) => 6,
+        // MC
+        ("i", _) => 7,
+        // SEE
+        ("Q", _) => 4,
+        // SIMD
+        ("^", _) => 1,
+        // DAQ
+        ("o", _) => 11,
+        // PTX
+        ("\\", _) => 5,
+        // SSU
+        (" I", _) => 8,
+        // SVS, FNT
+        (" L", _) | (" D", 0) => 9,
+        // SHS, QUAD
+        (" K" | " H", _) => 6,
+        // JFY
+        (" F", _) => 8,
+        // SCO, SPD
+        (" e", _) | (" S", 0) => 7,
+        (" S", 1) => 2,
+        // SAPV
+        (" ]", _) => 22,
+        // SCP
+        (" k", _) => 2,
+        _ => return None,
+    })
+}
+
+/// Checks the SGR parameters, the extended colors (`38`, `48` and `58`) taking their sub-parameters.
+fn validate_sgr(parameters: &[&str]) -> Result<(), SequenceError> {
+    let value = |i: usize| parameters.get(i).and_then(|p| if p.is_empty() { Some(0) } else { p.parse::<u16>().ok() });
+    let mut i = 0;
+    while i < parameters.len() {
+        i += match value(i) {
+            Some(0..=37 | 39..=47 | 49..=57 | 59..=65 | 90..=97 | 100..=107) => 1,
+            Some(38 | 48 | 58) => {
+                // Indexed (`5;n`) or direct (`2;r;g;b`) color.
+                let components = match value(i + 1) {
+                    Some(5) => 1,
+                    Some(2) => 3,
+                    _ => return Err(SequenceError::OutOfRange(i + 1)),
+                };
+                if let Some(bad) = (i + 2..i + 2 + components).find(|&j| !matches!(value(j), Some(n) if n <= 255)) {
+                    return Err(SequenceError::OutOfRange(bad));
+                }
+                2 + components
+            }
+            _ => return Err(SequenceError::OutOfRange(i)),
+        };
+    }
+    Ok(())
+}
+
+/// The error returned by [ControlSequence::try_new] and [ControlSequence::validate].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SequenceError {
+    /// The sequence has no final byte.
+    EmptyEnd,
+    /// The end is not made of intermediate bytes followed by a final byte.
+    InvalidEnd,
+    /// The argument at this index contains a separator or a byte which is not a parameter byte.
+    InvalidArgument(usize),
+    /// The parameter at this index is not a value defined for the control function.
+    OutOfRange(usize),
+}
+
+impl Display for SequenceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SequenceError::EmptyEnd => write!(f, "the control sequence has no final byte"),
+            SequenceError::InvalidEnd => write!(f, "invalid control sequence end"),
+            SequenceError::InvalidArgument(i) => write!(f, "invalid control sequence argument at index {}", i),
+            SequenceError::OutOfRange(i) => write!(f, "control sequence parameter out of range at index {}", i),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SequenceError {}
+
+/// The error returned by [ControlSequence::parse].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input does not start with a control sequence introducer.
+    NotASequence,
+    /// The input ends before the final byte of the control sequence.
+    Unterminated,
+    /// The byte at this offset cannot appear in a control sequence.
+    InvalidByte(usize),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseError::NotASequence => write!(f, "not a control sequence"),
+            ParseError::Unterminated => write!(f, "unterminated control sequence"),
+            ParseError::InvalidByte(offset) => write!(f, "invalid byte in control sequence at offset {}", offset),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// A control function : a control sequence, an escape sequence or a single control character.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum AnySequence {
+    Csi(ControlSequence),
+    Esc(EscapeSequence),
+    Control(char),
+}
+
+impl Display for AnySequence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AnySequence::Csi(sequence) => write!(f, "{}", sequence),
+            AnySequence::Esc(sequence) => write!(f, "{}", sequence),
+            AnySequence::Control(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+/// An element of a stream : a control function or text.
+///
+/// A single character converts into an element only if it is a C0 control character.
+///
+/// ### Example
+/// ```
+/// use coded_chars::control::AnsiElement;
+/// use coded_chars::cursor::set_position;
+/// use coded_chars::format::NEL;
+///
+/// let elements: Vec<AnsiElement> = vec![set_position(1, 1).into(), "Hello".into(), NEL.into()];
+/// assert_eq!(elements.iter().map(AnsiElement::to_string).collect::<String>(), "\x1b[1;1HHello\x1bE");
+/// assert!(AnsiElement::try_from('a').is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum AnsiElement {
+    Sequence(AnySequence),
+    Text(String),
+}
+
+impl Display for AnsiElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AnsiElement::Sequence(sequence) => write!(f, "{}", sequence),
+            AnsiElement::Text(text) => write!(f, "{}", text),
+        }
     }
-}
\ No newline at end of file
+}
+
+impl From<AnySequence> for AnsiElement {
+    fn from(sequence: AnySequence) -> Self { AnsiElement::Sequence(sequence) }
+}
+
+impl From<ControlSequence> for AnsiElement {
+    fn from(sequence: ControlSequence) -> Self { AnsiElement::Sequence(AnySequence::Csi(sequence)) }
+}
+
+impl From<EscapeSequence> for AnsiElement {
+    fn from(sequence: EscapeSequence) -> Self { AnsiElement::Sequence(AnySequence::Esc(sequence)) }
+}
+
+impl TryFrom<char> for AnsiElement {
+    type Error = InvalidControl;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '\x00'..='\x1F' => Ok(AnsiElement::Sequence(AnySequence::Control(c))),
+            _ => Err(InvalidControl(c)),
+        }
+    }
+}
+
+impl From<String> for AnsiElement {
+    fn from(text: String) -> Self { AnsiElement::Text(text) }
+}
+
+impl From<&str> for AnsiElement {
+    fn from(text: &str) -> Self { AnsiElement::Text(text.to_string()) }
+}
+
+/// The error returned when converting a character other than a C0 control character into an [AnsiElement].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidControl(pub char);
+
+impl Display for InvalidControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?} is not a C0 control character", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidControl {}
+
+/// An accumulator of control functions and text, written at once to avoid tearing.
+///
+/// ### Example
+/// ```
+/// use coded_chars::control::Sequence;
+/// use coded_chars::cursor::set_position;
+/// use coded_chars::presentation::select_graphic;
+///
+/// let output = Sequence::new()
+///     .push(set_position(5, 1))
+///     .push(select_graphic().fg_red().get())
+///     .text("hi")
+///     .build();
+/// assert_eq!(output, "\x1b[5;1H\x1b[31mhi");
+/// ```
+#[derive(Clone, Default)]
+pub struct Sequence {
+    content: String,
+}
+
+impl Sequence {
+    pub fn new() -> Self { Self::default() }
+
+    /// Appends a control sequence.
+    pub fn push(&mut self, sequence: ControlSequence) -> &mut Self { self.append(sequence) }
+
+    /// Appends an escape sequence.
+    pub fn push_escape(&mut self, sequence: EscapeSequence) -> &mut Self { self.append(sequence) }
+
+    /// Appends text.
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.content.push_str(text);
+        self
+    }
+
+    /// Returns the accumulated control functions and text.
+    pub fn build(&self) -> String { self.content.clone() }
+
+    /// Writes the accumulated control functions and text to `w` in a single write, without flushing it.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        w.write_all(self.content.as_bytes())
+    }
+
+    fn append(&mut self, sequence: impl Display) -> &mut Self {
+        self.content.push_str(&sequence.to_string());
+        self
+    }
+}
+
+impl Display for Sequence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::control::{AnsiElement, ControlSequence, InvalidControl, ParseError, Sequence, SequenceError};
+    use crate::cursor::DECSC;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_to_buffer() {
+        let mut buffer = vec![];
+        ControlSequence::new(&["2", "5"], "H").write_to(&mut buffer).unwrap();
+        ControlSequence::new(&[], "m").write_to(&mut buffer).unwrap();
+        assert_eq!(buffer, b"\x1b[2;5H\x1b[m");
+    }
+
+    #[cfg(feature = "crossterm")]
+    #[test]
+    fn crossterm_command() {
+        use crossterm::Command;
+
+        let sequence = ControlSequence::new(&["2", "5"], "H");
+        let mut ansi = String::new();
+        sequence.write_ansi(&mut ansi).unwrap();
+        assert_eq!(ansi, sequence.to_string());
+
+        let mut buffer = vec![];
+        crossterm::queue!(buffer, ControlSequence::private(&["25"], "l"), sequence).unwrap();
+        assert_eq!(buffer, b"\x1b[?25l\x1b[2;5H");
+    }
+
+    #[test]
+    fn private_parameters() {
+        assert_eq!(ControlSequence::private(&["1049"], "h").to_string(), "\x1b[?1049h");
+        assert_eq!(ControlSequence::private(&["1000", "1006"], "l").to_string(), "\x1b[?1000;1006l");
+        assert_eq!(ControlSequence::private(&[], "c").to_string(), "\x1b[?c");
+    }
+
+    #[test]
+    fn batched_sequence() {
+        let mut sequence = Sequence::new();
+        sequence.push_escape(DECSC).push(ControlSequence::new(&["2"], "J")).text("hi");
+        assert_eq!(sequence.to_string(), "\x1b7\x1b[2Jhi");
+        assert_eq!(sequence.build(), "\x1b7\x1b[2Jhi");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn batched_write() {
+        let mut buffer = vec![];
+        Sequence::new().push_escape(DECSC).text("hi").write_to(&mut buffer).unwrap();
+        assert_eq!(buffer, b"\x1b7hi");
+    }
+
+    #[test]
+    fn parse_sequences() {
+        let parsed = |bytes: &[u8]| ControlSequence::parse(bytes).map(|(sequence, len)| (sequence.to_string(), len));
+        assert_eq!(parsed(b"\x1b[1;1Hrest"), Ok(("\x1b[1;1H".to_string(), 6)));
+        assert_eq!(parsed(b"\x1b[2J"), Ok(("\x1b[2J".to_string(), 4)));
+        assert_eq!(parsed(b"\x1b[;5H"), Ok(("\x1b[;5H".to_string(), 5)));
+        assert_eq!(parsed(b"\x1b[H"), Ok(("\x1b[H".to_string(), 3)));
+        assert_eq!(parsed(b"\x9b?25l"), Ok(("\x1b[?25l".to_string(), 5)));
+    }
+
+    #[test]
+    fn parse_intermediates() {
+        let (sequence, len) = ControlSequence::parse(b"\x1b[0 H").unwrap();
+        assert_eq!((sequence.end.as_str(), len), (" H", 5));
+        assert_eq!(sequence.arguments, ["0"]);
+        let (sequence, _) = ControlSequence::parse(b"\x1b[ @").unwrap();
+        assert!(sequence.arguments.is_empty());
+        assert_eq!(sequence.end, " @");
+        assert_eq!(ControlSequence::parse(b"text").err(), Some(ParseError::NotASequence));
+        assert_eq!(ControlSequence::parse(b"\x1b[1;2").err(), Some(ParseError::Unterminated));
+        assert_eq!(ControlSequence::parse(b"\x1b[1\n").err(), Some(ParseError::InvalidByte(3)));
+    }
+
+    #[test]
+    fn parse_unterminated() {
+        assert_eq!(ControlSequence::parse(b"\x1b").err(), Some(ParseError::Unterminated));
+        let buffer = b"\x1b[2Jtext\x1b";
+        let (_, len) = ControlSequence::parse(buffer).unwrap();
+        assert_eq!(ControlSequence::parse(&buffer[len + 4..]).err(), Some(ParseError::Unterminated));
+        assert_eq!(ControlSequence::parse(b"\x1b[").err(), Some(ParseError::Unterminated));
+        assert_eq!(ControlSequence::parse(b"\x9b").err(), Some(ParseError::Unterminated));
+        assert_eq!(ControlSequence::parse(b"\x1b(B").err(), Some(ParseError::NotASequence));
+    }
+
+    #[test]
+    fn byte_len_matches_display() {
+        // A small linear congruential generator, to draw reproducible argument sets.
+        let mut seed = 0x2545F491u32;
+        let mut next = |bound: u32| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 16) % bound
+        };
+        for _ in 0..200 {
+            let arguments = (0..next(5)).map(|_| next(100_000).to_string()).collect::<Vec<_>>();
+            let arguments = arguments.iter().map(String::as_str).collect::<Vec<_>>();
+            let end = [" H", "m", "H", " @"][next(4) as usize];
+            for sequence in [ControlSequence::new(&arguments, end), ControlSequence::private(&arguments, end)] {
+                assert_eq!(sequence.byte_len(), sequence.to_string().len());
+            }
+        }
+    }
+
+    #[test]
+    fn debug_format() {
+        assert_eq!(format!("{:?}", ControlSequence::new(&["1", "1"], "H")), "CSI[1;1]H");
+        assert_eq!(format!("{:?}", ControlSequence::new(&["0"], " H")), "CSI[0] H");
+        assert_eq!(format!("{:?}", ControlSequence::private(&["1049"], "h")), "CSI[?1049]h");
+        assert_eq!(format!("{:?}", ControlSequence::new(&["\x1b"], "m")), "CSI[\\u{1b}]m");
+    }
+
+    #[test]
+    fn checked_sequence() {
+        assert_eq!(ControlSequence::try_new(&["1"], ""), Err(SequenceError::EmptyEnd));
+        assert_eq!(ControlSequence::try_new(&["1;2"], "H"), Err(SequenceError::InvalidArgument(0)));
+        assert_eq!(ControlSequence::try_new(&["1", "x"], "H"), Err(SequenceError::InvalidArgument(1)));
+        assert_eq!(ControlSequence::try_new(&["1"], "H "), Err(SequenceError::InvalidEnd));
+        assert_eq!(ControlSequence::try_new(&["1"], "HH"), Err(SequenceError::InvalidEnd));
+        assert_eq!(ControlSequence::try_new(&["0"], " H"), Ok(ControlSequence::new(&["0"], " H")));
+        assert_eq!(ControlSequence::try_new(&[], "m").map(|s| s.to_string()), Ok("\x1b[m".to_string()));
+    }
+
+    #[test]
+    fn parameter_ranges() {
+        assert_eq!(ControlSequence::new(&["9"], " I").validate(), Err(SequenceError::OutOfRange(0)));
+        assert_eq!(ControlSequence::new(&["8"], " I").validate(), Ok(()));
+        assert_eq!(ControlSequence::new(&["3"], "J").validate(), Err(SequenceError::OutOfRange(0)));
+        assert_eq!(ControlSequence::new(&["", "2"], " S").validate(), Ok(()));
+        assert_eq!(ControlSequence::new(&["1", "3"], " S").validate(), Err(SequenceError::OutOfRange(1)));
+        assert_eq!(ControlSequence::private(&["1049"], "h").validate(), Ok(()));
+        assert_eq!(ControlSequence::new(&["120", "40"], "H").validate(), Ok(()));
+    }
+
+    #[test]
+    fn graphic_parameters() {
+        assert_eq!(ControlSequence::new(&["1", "38;5;208", "48;2;0;128;255"], "m").validate(), Ok(()));
+        assert_eq!(ControlSequence::new(&["1", "66"], "m").validate(), Err(SequenceError::OutOfRange(1)));
+        assert_eq!(ControlSequence::new(&["38;5;256"], "m").validate(), Err(SequenceError::OutOfRange(2)));
+        assert_eq!(ControlSequence::new(&["48;2;0;0"], "m").validate(), Err(SequenceError::OutOfRange(4)));
+        assert_eq!(ControlSequence::new(&["38", "1"], "m").validate(), Err(SequenceError::OutOfRange(1)));
+    }
+
+    #[test]
+    fn elements_render() {
+        let elements: Vec<AnsiElement> = vec![
+            DECSC.into(),
+            ControlSequence::new(&["2"], "J").into(),
+            '\x07'.try_into().unwrap(),
+            String::from("done").into(),
+        ];
+        let rendered = elements.iter().map(AnsiElement::to_string).collect::<String>();
+        assert_eq!(rendered, "\x1b7\x1b[2J\x07done");
+    }
+
+    #[test]
+    fn elements_reject_text_characters() {
+        assert_eq!(AnsiElement::try_from('a').err(), Some(InvalidControl('a')));
+        assert_eq!(AnsiElement::try_from('\x7F').err(), Some(InvalidControl('\x7F')));
+        assert_eq!(AnsiElement::try_from('\u{9B}').err(), Some(InvalidControl('\u{9B}')));
+        assert!(AnsiElement::try_from('\x1b').is_ok());
+    }
+}
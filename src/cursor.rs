@@ -1,8 +1,15 @@
 //! This module helps move and set the cursor position.
 
-use std::fmt::{Display, Formatter};
-use crate::control::ControlSequence;
+use alloc::string::ToString;
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::Write;
+use crate::control::{parse_numbers, ControlSequence, ParseError};
+use crate::escape::{escape, EscapeSequence};
 use crate::format::TabulationControl;
+use crate::parser::csi_parts;
 
 /// # CTC - Cursor tabulation control
 ///
@@ -27,15 +34,104 @@ pub fn position_report(l: usize, c: usize) -> ControlSequence {
     ControlSequence::new(&[&l.to_string(), &c.to_string()], "R")
 }
 
+/// # CUP - Cursor position, without parameters
+///
+/// Moves the cursor to the first line and the first column, the parameters defaulting to 1. This is the
+/// shorter form of `set_position(1, 1)`, which always prints its parameters.
+pub const HOME: &str = "\x1b[H";
+
+/// # CUU - Cursor up, without parameter
+pub const UP_ONE: &str = "\x1b[A";
+
+/// # CUD - Cursor down, without parameter
+pub const DOWN_ONE: &str = "\x1b[B";
+
+/// # CUF - Cursor right, without parameter
+pub const RIGHT_ONE: &str = "\x1b[C";
+
+/// # CUB - Cursor left, without parameter
+pub const LEFT_ONE: &str = "\x1b[D";
+
 /// # CUP - Cursor position
 ///
 /// CUP causes the active presentation position to be moved in the presentation component to the n-th line
 /// position according to the line progression and to the m-th character position according to the character
 /// path, where n equals the value of `l` and m equals the value of `c`.
+///
+/// Lines and columns are numbered from 1, but `l` and `c` are not checked : see [try_set_position].
 pub fn set_position(l: usize, c: usize) -> ControlSequence {
     ControlSequence::new(&[&l.to_string(), &c.to_string()], "H")
 }
 
+/// A position in the presentation component, lines and columns being numbered from 1.
+///
+/// A tuple converts into a position as `(line, column)`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<(usize, usize)> for Position {
+    fn from((line, column): (usize, usize)) -> Self {
+        Position { line, column }
+    }
+}
+
+/// # CUP - Cursor position
+///
+/// Same as [set_position], with the line and the column named at the call site.
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::{move_to, Position};
+///
+/// assert_eq!(move_to(Position { line: 5, column: 1 }).to_string(), "\x1b[5;1H");
+/// assert_eq!(move_to((5, 1).into()).to_string(), "\x1b[5;1H");
+/// ```
+pub fn move_to(position: Position) -> ControlSequence {
+    set_position(position.line, position.column)
+}
+
+/// # CUP - Cursor position
+///
+/// Same as [set_position], but returns a [PositionError] if `l` or `c` is zero, lines and columns being
+/// numbered from 1.
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::{try_set_position, PositionError};
+///
+/// assert_eq!(try_set_position(5, 1).unwrap().to_string(), "\x1b[5;1H");
+/// assert_eq!(try_set_position(5, 0).err(), Some(PositionError::ColumnZero));
+/// ```
+pub fn try_set_position(l: usize, c: usize) -> Result<ControlSequence, PositionError> {
+    match (l, c) {
+        (0, _) => Err(PositionError::LineZero),
+        (_, 0) => Err(PositionError::ColumnZero),
+        _ => Ok(set_position(l, c)),
+    }
+}
+
+/// The error returned by [try_set_position] when a coordinate is zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PositionError {
+    LineZero,
+    ColumnZero,
+}
+
+impl Display for PositionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", match self {
+            PositionError::LineZero => "the line must be at least 1",
+            PositionError::ColumnZero => "the column must be at least 1",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PositionError {}
+
 /// A struct representing the cursor directions.
 ///
 /// To use with the function [move_cursor].
@@ -77,7 +173,7 @@ pub enum Direction {
 }
 
 impl Display for Direction {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Direction::Up => "A",
             Direction::Down => "B",
@@ -104,6 +200,149 @@ pub fn move_cursor(direction: Direction, n: usize) -> ControlSequence {
     ControlSequence::new(&[&n.to_string()], &direction.to_string())
 }
 
+/// Moves the cursor up by `n` lines (CUU), see [move_cursor].
+pub fn up(n: usize) -> ControlSequence { move_cursor(Direction::Up, n) }
+
+/// Moves the cursor down by `n` lines (CUD), see [move_cursor].
+pub fn down(n: usize) -> ControlSequence { move_cursor(Direction::Down, n) }
+
+/// Moves the cursor right by `n` characters (CUF), see [move_cursor].
+pub fn right(n: usize) -> ControlSequence { move_cursor(Direction::Forward, n) }
+
+/// Moves the cursor left by `n` characters (CUB), see [move_cursor].
+pub fn left(n: usize) -> ControlSequence { move_cursor(Direction::Backward, n) }
+
+/// Decodes a cursor movement sequence, as emitted by [move_cursor], into its direction and count.
+///
+/// An omitted parameter defaults to 1. Returns `None` if `s` is not exactly one of CUU, CUD, CUF, CUB,
+/// CNL or CPL.
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::{move_cursor, parse_movement, Direction};
+///
+/// let sequence = move_cursor(Direction::Forward, 3).to_string();
+/// assert!(matches!(parse_movement(&sequence), Some((Direction::Forward, 3))));
+/// ```
+pub fn parse_movement(s: &str) -> Option<(Direction, usize)> {
+    let (parameters, end, len) = csi_parts(s)?;
+    if len != s.len() {
+        return None;
+    }
+    let direction = match end {
+        "A" => Direction::Up,
+        "B" => Direction::Down,
+        "C" => Direction::Forward,
+        "D" => Direction::Backward,
+        "E" => Direction::NextLine,
+        "F" => Direction::PreviousLine,
+        _ => return None,
+    };
+    let n = match parameters {
+        "" => 1,
+        n => n.parse().ok()?,
+    };
+    Some((direction, n))
+}
+
+/// Decodes the ACTIVE POSITION REPORT (CPR) sent by a terminal in reply to
+/// `report_status(StatusReport::PositionWaiting)`, see [crate::device::report_status].
+///
+/// The reply has the form `CSI l ; c R`, an omitted parameter defaulting to 1. Bytes following the reply are
+/// ignored.
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::{parse_position_report, Position};
+///
+/// assert_eq!(parse_position_report(b"\x1b[12;40R"), Ok(Position { line: 12, column: 40 }));
+/// ```
+pub fn parse_position_report(bytes: &[u8]) -> Result<Position, ParseError> {
+    let numbers = parse_numbers(bytes, b'R', false)?;
+    if let Some((offset, _)) = numbers.get(2) {
+        // The separator preceding the extra parameter
+        return Err(ParseError::InvalidByte(offset - 1));
+    }
+    let value = |i: usize| numbers.get(i).and_then(|(_, n)| *n).unwrap_or(1);
+    Ok(Position { line: value(0), column: value(1) })
+}
+
+/// # DECSC - Save cursor
+///
+/// Saves the cursor position and the graphic rendition, to be restored by [DECRC].
+///
+/// This DEC private function is widely implemented by terminals but is not part of ECMA-48.
+pub const DECSC: EscapeSequence = escape('7');
+
+/// # DECRC - Restore cursor
+///
+/// Restores the cursor position and the graphic rendition saved by [DECSC].
+///
+/// This DEC private function is widely implemented by terminals but is not part of ECMA-48.
+pub const DECRC: EscapeSequence = escape('8');
+
+/// # DECTCEM - Show cursor
+///
+/// Makes the cursor visible.
+///
+/// This DEC private mode (`?25`) is widely implemented by terminals but is not part of ECMA-48.
+///
+/// - The printed sequence is : `\x1b[?25h`
+pub fn show() -> ControlSequence {
+    ControlSequence::private(&["25"], "h")
+}
+
+/// # DECTCEM - Hide cursor
+///
+/// Makes the cursor invisible, see [show].
+///
+/// - The printed sequence is : `\x1b[?25l`
+pub fn hide() -> ControlSequence {
+    ControlSequence::private(&["25"], "l")
+}
+
+/// # SCP - Save cursor position
+///
+/// Saves the cursor position, to be restored by [restore_position].
+///
+/// This private control sequence (`CSI s`) is widely implemented by terminals but is not part of ECMA-48,
+/// where the final byte `s` is not assigned. Unlike [DECSC], the graphic rendition is not saved.
+pub fn save_position() -> ControlSequence {
+    ControlSequence::new(&[], "s")
+}
+
+/// # RCP - Restore cursor position
+///
+/// Restores the cursor position saved by [save_position].
+///
+/// This private control sequence (`CSI u`) is widely implemented by terminals but is not part of ECMA-48,
+/// where the final byte `u` is not assigned.
+pub fn restore_position() -> ControlSequence {
+    ControlSequence::new(&[], "u")
+}
+
+/// Runs `f` between [DECSC] and [DECRC], so the cursor moves made by `f` do not disturb the cursor position.
+///
+/// The cursor is always restored, even if `f` returns an error. The error returned by `f` takes precedence
+/// over any error raised while restoring.
+///
+/// ### Example
+/// ```
+/// use std::io::{stdout, Write};
+/// use coded_chars::cursor::{set_position, with_saved_cursor};
+///
+/// with_saved_cursor(&mut stdout(), |out| {
+///     write!(out, "{}Status : OK", set_position(1, 1))
+/// }).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn with_saved_cursor<W: Write, F: FnOnce(&mut W) -> io::Result<()>>(w: &mut W, f: F) -> io::Result<()> {
+    write!(w, "{}", DECSC)?;
+    let result = f(w);
+    let restored = write!(w, "{}", DECRC).and_then(|_| w.flush());
+    result.and(restored)
+}
+
 /// # CBT - Cursor backward tabulation
 ///
 /// CBT causes the active presentation position to be moved to the character position corresponding to the
@@ -127,3 +366,91 @@ pub fn tabulation_forward(n: usize) -> ControlSequence {
 pub fn line_tabulation(n: usize) -> ControlSequence {
     ControlSequence::new(&[&n.to_string()], "Y")
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::control::ParseError;
+    use crate::cursor::{down, hide, left, move_cursor, move_to, parse_movement, parse_position_report, restore_position, right, save_position, set_position, show, try_set_position, up, Direction, Position, PositionError, DOWN_ONE, HOME, LEFT_ONE, RIGHT_ONE, UP_ONE};
+
+    #[test]
+    fn movement_round_trip() {
+        assert!(matches!(parse_movement("\x1b[3C"), Some((Direction::Forward, 3))));
+        assert!(matches!(parse_movement("\x1b[A"), Some((Direction::Up, 1))));
+        assert!(matches!(parse_movement(&move_cursor(Direction::PreviousLine, 12).to_string()), Some((Direction::PreviousLine, 12))));
+        assert!(parse_movement("\x1b[1;1H").is_none());
+        assert!(parse_movement("\x1b[3Ctext").is_none());
+        assert!(parse_movement("\x1b[3").is_none());
+    }
+
+    #[test]
+    fn movement_aliases() {
+        assert_eq!(up(2).to_string(), "\x1b[2A");
+        assert_eq!(down(3).to_string(), "\x1b[3B");
+        assert_eq!(right(4).to_string(), "\x1b[4C");
+        assert_eq!(left(5).to_string(), "\x1b[5D");
+    }
+
+    #[test]
+    fn checked_position() {
+        assert_eq!(try_set_position(0, 1).err(), Some(PositionError::LineZero));
+        assert_eq!(try_set_position(0, 0).err(), Some(PositionError::LineZero));
+        assert_eq!(try_set_position(3, 0).err(), Some(PositionError::ColumnZero));
+        assert_eq!(try_set_position(1, 1).unwrap().to_string(), "\x1b[1;1H");
+    }
+
+    #[test]
+    fn sequence_into_string() {
+        assert_eq!(String::from(set_position(1, 1)), "\x1b[1;1H");
+        assert_eq!(set_position(1, 1).to_sequence_string(), "\x1b[1;1H");
+    }
+
+    #[test]
+    fn position_report_reply() {
+        assert_eq!(parse_position_report(b"\x1b[12;40R"), Ok(Position { line: 12, column: 40 }));
+        assert_eq!(parse_position_report(b"\x1b[;7Rrest"), Ok(Position { line: 1, column: 7 }));
+        assert_eq!(parse_position_report(b"\x1b[R"), Ok(Position { line: 1, column: 1 }));
+        assert_eq!(parse_position_report(b"\x1b[12;40H"), Err(ParseError::InvalidByte(7)));
+        assert_eq!(parse_position_report(b"\x1b[1?;4R"), Err(ParseError::InvalidByte(3)));
+        assert_eq!(parse_position_report(b"\x1b[1;2;3R"), Err(ParseError::InvalidByte(5)));
+        assert_eq!(parse_position_report(b"\x1b[12;4"), Err(ParseError::Unterminated));
+    }
+
+    #[test]
+    fn parameterless_moves() {
+        assert_eq!(HOME, "\x1b[H");
+        assert!(matches!(parse_movement(UP_ONE), Some((Direction::Up, 1))));
+        assert!(matches!(parse_movement(DOWN_ONE), Some((Direction::Down, 1))));
+        assert!(matches!(parse_movement(RIGHT_ONE), Some((Direction::Forward, 1))));
+        assert!(matches!(parse_movement(LEFT_ONE), Some((Direction::Backward, 1))));
+    }
+
+    #[test]
+    fn named_position() {
+        assert_eq!(move_to(Position { line: 5, column: 1 }).to_string(), "\x1b[5;1H");
+        assert_eq!(Position::from((2, 7)), Position { line: 2, column: 7 });
+        assert_eq!(move_to((2, 7).into()).to_string(), "\x1b[2;7H");
+    }
+
+    #[test]
+    fn cursor_visibility() {
+        assert_eq!(show().to_string(), "\x1b[?25h");
+        assert_eq!(hide().to_string(), "\x1b[?25l");
+    }
+
+    #[test]
+    fn save_restore_position() {
+        assert_eq!(save_position().to_string(), "\x1b[s");
+        assert_eq!(restore_position().to_string(), "\x1b[u");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn saved_cursor_brackets_output() {
+        use std::io::Write;
+        use crate::cursor::with_saved_cursor;
+
+        let mut out = Vec::new();
+        with_saved_cursor(&mut out, |w| write!(w, "{}status", set_position(1, 1))).unwrap();
+        assert_eq!(out, b"\x1b7\x1b[1;1Hstatus\x1b8");
+    }
+}
@@ -1,5 +1,6 @@
 //! Various delimiters.
 
+use alloc::string::String;
 use crate::escape::{escape, EscapeSequence};
 
 /// Application program command
@@ -21,4 +22,37 @@ pub const PM: EscapeSequence = escape('^');
 pub const SOS: EscapeSequence = escape('X');
 
 /// String terminator
-pub const ST: EscapeSequence = escape('\\');
\ No newline at end of file
+pub const ST: EscapeSequence = escape('\\');
+
+/// Removes from `s` the characters which may not appear in the command string of a control string (APC,
+/// DCS, OSC, PM).
+///
+/// A command string may only contain format effectors (`0x08` to `0x0D`) and graphic characters. Any other
+/// control character is removed, in particular ESC, BEL and the C1 [ST], which would terminate the string
+/// early, and CAN or SUB, which would abort it.
+///
+/// ### Example
+/// ```
+/// use coded_chars::delimiters::{escape_string_payload, OSC, ST};
+///
+/// let title = "Hello\x1b\\\x1b[2JWorld\x07";
+/// println!("{}0;{}{}", OSC, escape_string_payload(title), ST);
+/// assert_eq!(escape_string_payload(title), "Hello\\[2JWorld");
+/// ```
+pub fn escape_string_payload(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_control() || ('\x08'..='\x0D').contains(c))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::delimiters::escape_string_payload;
+
+    #[test]
+    fn payload_terminators() {
+        assert_eq!(escape_string_payload("a\x1b\\b\x07c\u{9C}d\x18e"), "a\\bcde");
+        assert_eq!(escape_string_payload("tab\there"), "tab\there");
+        assert_eq!(escape_string_payload("caf\u{e9}"), "caf\u{e9}");
+    }
+}
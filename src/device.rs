@@ -1,8 +1,14 @@
 //! Control sequences that are devices-related.
 
-use std::fmt::{Display, Formatter};
-use crate::control::ControlSequence;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use crate::control::{parse_numbers, ControlSequence, ParseError};
+use crate::delimiters::{DCS, ST};
 use crate::escape::{escape, EscapeSequence};
+use crate::presentation::GraphicSelection;
+use crate::sgr::selection;
 
 /// # Device control 1
 ///
@@ -60,6 +66,89 @@ pub fn attributes(n: usize) -> ControlSequence {
     ControlSequence::new(&[&n.to_string()], "c")
 }
 
+/// Decodes the `CSI ? … c` reply sent by a terminal to `attributes(0)`, see [attributes].
+///
+/// Returns the attributes in order, an omitted one being 0. Bytes following the reply are ignored.
+///
+/// ### Example
+/// ```
+/// use coded_chars::device::parse_attributes;
+///
+/// // A VT100 with advanced video option
+/// assert_eq!(parse_attributes(b"\x1b[?1;2c"), Ok(vec![1, 2]));
+/// ```
+pub fn parse_attributes(bytes: &[u8]) -> Result<Vec<u16>, ParseError> {
+    parse_numbers(bytes, b'c', true)?
+        .into_iter()
+        .map(|(offset, n)| u16::try_from(n.unwrap_or(0)).map_err(|_| ParseError::InvalidByte(offset)))
+        .collect()
+}
+
+/// # DECRQSS - Request selection or setting, for SGR
+///
+/// Requests the graphic rendition currently established, the terminal replies with `DCS 1 $ r … m ST`, see
+/// [parse_sgr_report].
+///
+/// - The printed sequence is : `\x1bP$qm\x1b\\`
+///
+/// ### Note
+/// DECRQSS is a DEC function widely implemented by terminals, it is not part of ECMA-48.
+pub fn request_current_sgr() -> String {
+    format!("{}$qm{}", DCS, ST)
+}
+
+/// Decodes the `DCS 1 $ r … m ST` reply sent by a terminal to [request_current_sgr].
+///
+/// The reply may be introduced by **ESC** `P` or by the 8-bit DCS byte (`0x90`), and terminated by **ESC** `\`
+/// or by the 8-bit ST byte (`0x9C`). A reply starting with `0 $ r`, sent when the request is not supported,
+/// is rejected. Bytes following the reply are ignored.
+///
+/// ### Example
+/// ```
+/// use coded_chars::device::parse_sgr_report;
+///
+/// let style = parse_sgr_report(b"\x1bP1$r0;1;31m\x1b\\").unwrap();
+/// assert_eq!(style.to_string(), "\x1b[0;1;31m");
+/// ```
+pub fn parse_sgr_report(bytes: &[u8]) -> Result<GraphicSelection, ParseError> {
+    let from = match bytes {
+        [0x1B, b'P', ..] => 2,
+        [0x90, ..] => 1,
+        _ => return Err(ParseError::NotASequence),
+    };
+    for (i, expected) in b"1$r".iter().enumerate() {
+        match bytes.get(from + i) {
+            None => return Err(ParseError::Unterminated),
+            Some(b) if b == expected => {}
+            Some(_) => return Err(ParseError::InvalidByte(from + i)),
+        }
+    }
+
+    let parameters_from = from + 3;
+    let parameters_len = bytes[parameters_from..].iter().take_while(|b| b.is_ascii_digit() || **b == b';').count();
+    let final_at = parameters_from + parameters_len;
+    match bytes.get(final_at) {
+        None => return Err(ParseError::Unterminated),
+        Some(b'm') => {}
+        Some(_) => return Err(ParseError::InvalidByte(final_at)),
+    }
+    match &bytes[final_at + 1..] {
+        [0x1B, b'\\', ..] | [0x9C, ..] => {}
+        [] | [0x1B] => return Err(ParseError::Unterminated),
+        _ => return Err(ParseError::InvalidByte(final_at + 1)),
+    }
+
+    // The parameters only hold ASCII bytes at this point.
+    let parameters = core::str::from_utf8(&bytes[parameters_from..final_at]).map_err(|_| ParseError::InvalidByte(parameters_from))?;
+    let mut codes = Vec::new();
+    let mut offset = parameters_from;
+    for parameter in parameters.split(';') {
+        codes.push(if parameter.is_empty() { 0 } else { parameter.parse::<u16>().map_err(|_| ParseError::InvalidByte(offset))? });
+        offset += parameter.len() + 1;
+    }
+    Ok(selection(&codes))
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum StatusReport {
     /// Ready, no malfunction detected.
@@ -80,7 +169,7 @@ pub enum StatusReport {
 }
 
 impl Display for StatusReport {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             StatusReport::Ready => "0",
             StatusReport::BusyRetry => "1",
@@ -102,7 +191,7 @@ impl Display for StatusReport {
 /// or 4 [StatusReport::ErrorWaiting] may be sent either unsolicited or as a response to a request such as a DSR with
 /// a parameter value 5 [StatusReport::MessageWaiting] or MESSAGE WAITING (MW).
 pub fn report_status(status_report: StatusReport) -> ControlSequence {
-    ControlSequence::new(&[&status_report.to_string()], "c")
+    ControlSequence::new(&[&status_report.to_string()], "n")
 }
 
 /// # FNK - Function key
@@ -132,7 +221,7 @@ pub enum ControlString {
 }
 
 impl Display for ControlString {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             ControlString::SRTMDiagnose => "1",
             ControlString::Ecma35DCRS => "2"
@@ -147,7 +236,7 @@ impl Display for ControlString {
 /// The parameter value of IGS identifies a graphic character repertoire registered in accordance with
 /// ISO/IEC 7350.
 pub fn identify_graphic_sub(n: usize) -> ControlSequence {
-    ControlSequence::new(&[&n.to_string()], " W")
+    ControlSequence::new(&[&n.to_string()], " M")
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -171,7 +260,7 @@ pub enum CopyStatus {
 }
 
 impl Display for CopyStatus {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             CopyStatus::InitTo1 => "0",
             CopyStatus::InitFrom1 => "1",
@@ -201,4 +290,39 @@ pub fn media_copy(copy_status: CopyStatus) -> ControlSequence {
 /// another sheet to be loaded into the printing device from a specified paper bin.
 pub fn eject_and_feed(bin: usize, stacker: usize) -> ControlSequence {
     ControlSequence::new(&[&bin.to_string(), &stacker.to_string()], " Y")
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::control::ParseError;
+    use crate::device::{identify_graphic_sub, parse_attributes, parse_sgr_report, report_status, request_current_sgr, StatusReport};
+
+    #[test]
+    fn sgr_report() {
+        assert_eq!(request_current_sgr(), "\x1bP$qm\x1b\\");
+        assert_eq!(parse_sgr_report(b"\x1bP1$r0;1m\x1b\\").map(|style| style.codes()), Ok(vec![0, 1]));
+        assert_eq!(parse_sgr_report(b"\x901$r38;5;208m\x9c").map(|style| style.modes().to_vec()), Ok(vec!["38;5;208".to_string()]));
+        assert_eq!(parse_sgr_report(b"\x1bP0$r\x1b\\").map(|style| style.codes()), Err(ParseError::InvalidByte(2)));
+        assert_eq!(parse_sgr_report(b"\x1bP1$r0;1m").map(|style| style.codes()), Err(ParseError::Unterminated));
+        assert_eq!(parse_sgr_report(b"\x1b[0m").map(|style| style.codes()), Err(ParseError::NotASequence));
+    }
+
+    #[test]
+    fn identify_graphic_sub_final() {
+        assert_eq!(identify_graphic_sub(4).to_string(), "\x1b[4 M");
+    }
+
+    #[test]
+    fn report_status_final() {
+        assert_eq!(report_status(StatusReport::PositionWaiting).to_string(), "\x1b[6n");
+    }
+
+    #[test]
+    fn attributes_reply() {
+        assert_eq!(parse_attributes(b"\x1b[?1;2c"), Ok(vec![1, 2]));
+        assert_eq!(parse_attributes(b"\x1b[?62;;22crest"), Ok(vec![62, 0, 22]));
+        assert_eq!(parse_attributes(b"\x1b[?c"), Ok(vec![]));
+        assert_eq!(parse_attributes(b"\x1b[1;2c"), Err(ParseError::InvalidByte(2)));
+        assert_eq!(parse_attributes(b"\x1b[?70000c"), Err(ParseError::InvalidByte(3)));
+        assert_eq!(parse_attributes(b"\x1b[?1;2R"), Err(ParseError::InvalidByte(6)));
+    }
+}
@@ -1,7 +1,11 @@
 //! This module provides control function that change the display.
 
-use std::fmt::{Display, Formatter};
+use alloc::string::{String, ToString};
+use alloc::format;
+use core::fmt::{Display, Formatter};
 use crate::control::ControlSequence;
+use crate::cursor::set_position;
+use crate::presentation::repeat_char;
 
 /// # PP - Preceding page
 ///
@@ -20,12 +24,154 @@ pub fn next_page(n: usize) -> ControlSequence {
     ControlSequence::new(&[&n.to_string()], "U")
 }
 
+/// # DECSTBM - Set top and bottom margins
+///
+/// Restricts scrolling to the lines `top` to `bottom`, numbered from 1.
+///
+/// - The printed sequence is : `\x1b[{top};{bottom}r`
+///
+/// ### Note
+/// DECSTBM is a DEC function widely implemented by terminals, it is not part of ECMA-48.
+pub fn set_scroll_region(top: usize, bottom: usize) -> ControlSequence {
+    ControlSequence::new(&[&top.to_string(), &bottom.to_string()], "r")
+}
+
+/// # DECSTBM - Set top and bottom margins
+///
+/// Resets the scrolling region to the whole screen.
+///
+/// - The printed sequence is : `\x1b[r`
+pub fn reset_scroll_region() -> ControlSequence {
+    ControlSequence::new(&[], "r")
+}
+
+/// # DECOM - Origin mode
+///
+/// Makes the cursor positions relative to the scrolling region : after DECOM is set, [set_position] moves
+/// the cursor to the line `l` of the region set by [set_scroll_region], not of the screen, and the cursor
+/// cannot leave the region. Setting or resetting DECOM moves the cursor to the home position.
+///
+/// - The printed sequence is : `\x1b[?6h`
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::set_position;
+/// use coded_chars::display::{disable_origin_mode, enable_origin_mode, set_scroll_region};
+///
+/// // The line 1 of the region is the line 5 of the screen.
+/// print!("{}{}{}Header", set_scroll_region(5, 20), enable_origin_mode(), set_position(1, 1));
+/// print!("{}", disable_origin_mode());
+/// ```
+///
+/// ### Note
+/// DECOM is a DEC private mode widely implemented by terminals, it is not part of ECMA-48.
+pub fn enable_origin_mode() -> ControlSequence {
+    ControlSequence::private(&["6"], "h")
+}
+
+/// # DECOM - Origin mode
+///
+/// Makes the cursor positions relative to the screen again, see [enable_origin_mode].
+///
+/// - The printed sequence is : `\x1b[?6l`
+pub fn disable_origin_mode() -> ControlSequence {
+    ControlSequence::private(&["6"], "l")
+}
+
+/// Resets the scrolling region to the whole screen and moves the cursor to the home position.
+///
+/// - The printed sequence is : `\x1b[r\x1b[H`
+///
+/// ### Note
+/// The scrolling region is set by [set_scroll_region].
+pub fn reset_layout() -> String {
+    format!("{}{}", reset_scroll_region(), ControlSequence::new(&[], "H"))
+}
+
+/// Returns the stream filling `width` columns on `height` lines with `ch`, from line `top` and column `left`.
+///
+/// Each line is reached with CUP and filled with [repeat_char].
+///
+/// ### Example
+/// ```
+/// use coded_chars::display::fill_rect;
+///
+/// assert_eq!(fill_rect(1, 1, 2, 2, '#'), "\x1b[1;1H#\x1b[1b\x1b[2;1H#\x1b[1b");
+/// ```
+pub fn fill_rect(top: usize, left: usize, width: usize, height: usize, ch: char) -> String {
+    if width == 0 {
+        return String::new();
+    }
+    let line = repeat_char(ch, width);
+    (top..top + height)
+        .map(|l| format!("{}{}", set_position(l, left), line))
+        .collect()
+}
+
+/// Returns the stream drawing a horizontal line of `width` characters `ch`, from line `top` and column `left`.
+///
+/// ### Example
+/// ```
+/// use coded_chars::display::horizontal_rule;
+///
+/// assert_eq!(horizontal_rule(3, 1, 10, '-'), "\x1b[3;1H-\x1b[9b");
+/// ```
+pub fn horizontal_rule(top: usize, left: usize, width: usize, ch: char) -> String {
+    fill_rect(top, left, width, 1, ch)
+}
+
+/// Returns the stream drawing a vertical line of `height` characters `ch`, from line `top` in the column
+/// `col`.
+///
+/// Each line is reached with CUP, a `height` of 0 draws nothing.
+///
+/// ### Example
+/// ```
+/// use coded_chars::display::vertical_rule;
+///
+/// assert_eq!(vertical_rule(1, 5, 2, '|'), "\x1b[1;5H|\x1b[2;5H|");
+/// ```
+pub fn vertical_rule(top: usize, col: usize, height: usize, ch: char) -> String {
+    fill_rect(top, col, 1, height, ch)
+}
 
 /// Use this function to call the control functions `SD`, `SL`, `ST` and `SR`.
 pub fn scroll(n: usize, scroll_direction: ScrollDirection) -> ControlSequence {
     ControlSequence::new(&[&n.to_string()], &scroll_direction.to_string())
 }
 
+/// Same as [scroll], but returns a [ZeroScroll] error if `n` is zero.
+///
+/// A parameter value of `0` is not a no-op : it is the default parameter value, which some terminals treat
+/// as `1`.
+///
+/// ### Example
+/// ```
+/// use coded_chars::display::{try_scroll, ScrollDirection, ZeroScroll};
+///
+/// assert_eq!(try_scroll(2, ScrollDirection::Left).unwrap().to_string(), "\x1b[2 @");
+/// assert_eq!(try_scroll(0, ScrollDirection::Up).err(), Some(ZeroScroll));
+/// ```
+pub fn try_scroll(n: usize, scroll_direction: ScrollDirection) -> Result<ControlSequence, ZeroScroll> {
+    match n {
+        0 => Err(ZeroScroll),
+        _ => Ok(scroll(n, scroll_direction)),
+    }
+}
+
+/// The error returned by [try_scroll] when the amount to scroll is zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ZeroScroll;
+
+impl Display for ZeroScroll {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "the amount to scroll must be at least 1")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ZeroScroll {}
+
 #[derive(Copy, Clone, Debug)]
 pub enum ScrollDirection {
     /// # SD - Scroll down
@@ -66,7 +212,7 @@ pub enum ScrollDirection {
 }
 
 impl Display for ScrollDirection {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             ScrollDirection::Down => "T",
             ScrollDirection::Left => " @",
@@ -75,3 +221,51 @@ impl Display for ScrollDirection {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::display::{disable_origin_mode, enable_origin_mode, fill_rect, horizontal_rule, vertical_rule, reset_layout, reset_scroll_region, scroll, set_scroll_region, try_scroll, ScrollDirection, ZeroScroll};
+
+    #[test]
+    fn layout_reset() {
+        assert_eq!(reset_layout(), "\x1b[r\x1b[H");
+    }
+
+    #[test]
+    fn rules() {
+        assert_eq!(vertical_rule(2, 5, 3, '|'), "\x1b[2;5H|\x1b[3;5H|\x1b[4;5H|");
+        assert_eq!(vertical_rule(2, 5, 0, '|'), "");
+        assert_eq!(horizontal_rule(2, 5, 3, '-'), "\x1b[2;5H-\x1b[2b");
+        assert_eq!(horizontal_rule(2, 5, 0, '-'), "");
+    }
+
+    #[test]
+    fn origin_mode() {
+        assert_eq!(enable_origin_mode().to_string(), "\x1b[?6h");
+        assert_eq!(disable_origin_mode().to_string(), "\x1b[?6l");
+    }
+
+    #[test]
+    fn scroll_region() {
+        assert_eq!(set_scroll_region(2, 10).to_string(), "\x1b[2;10r");
+        assert_eq!(reset_scroll_region().to_string(), "\x1b[r");
+    }
+
+    #[test]
+    fn scroll_finals() {
+        assert_eq!(scroll(2, ScrollDirection::Down).to_string(), "\x1b[2T");
+        assert_eq!(scroll(2, ScrollDirection::Left).to_string(), "\x1b[2 @");
+        assert_eq!(scroll(2, ScrollDirection::Right).to_string(), "\x1b[2 A");
+        assert_eq!(scroll(2, ScrollDirection::Up).to_string(), "\x1b[2S");
+        assert_eq!(try_scroll(0, ScrollDirection::Left).err(), Some(ZeroScroll));
+        assert_eq!(try_scroll(1, ScrollDirection::Right).map(|s| s.to_string()), Ok("\x1b[1 A".into()));
+    }
+
+    #[test]
+    fn rectangle_fill() {
+        assert_eq!(fill_rect(1, 1, 2, 2, '#'), "\x1b[1;1H#\x1b[1b\x1b[2;1H#\x1b[1b");
+        assert_eq!(fill_rect(3, 5, 1, 1, '.'), "\x1b[3;5H.");
+        assert_eq!(fill_rect(1, 1, 0, 3, '#'), "");
+        assert_eq!(fill_rect(1, 1, 3, 0, '#'), "");
+    }
+}
@@ -1,6 +1,7 @@
 //! Helps edit text.
 
-use std::fmt::{Display, Formatter};
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter};
 use crate::control::ControlSequence;
 
 /// # ICH - Insert character
@@ -57,8 +58,11 @@ pub fn insert_char(n: usize) -> ControlSequence {
 /// line and of adjacent lines are shifted away from the active line. The contents of n lines at the other end
 /// of the shifted part are removed. The active data position is moved to the line home position in the active
 /// line. The line home position is established by the parameter value of SET LINE HOME (SLH).
+///
+/// ### Note
+/// Up to version 3.0.1, this function wrongly emitted the final byte of EL (`K`) instead of `L`.
 pub fn insert_line(n: usize) -> ControlSequence {
-    ControlSequence::new(&[&n.to_string()], "K")
+    ControlSequence::new(&[&n.to_string()], "L")
 }
 
 /// # DCH - Delete character
@@ -159,15 +163,69 @@ pub fn erase(area_position: AreaPosition) -> ControlSequence {
     ControlSequence::new(&[&area_position.to_string()], "O")
 }
 
-#[derive(Copy, Clone, Debug)]
+/// The extent erased by ED, EL, EA and EF, see [erase_in_page], [erase_in_line], [erase] and
+/// [erase_in_field].
+///
+/// ### Example
+/// ```
+/// use coded_chars::editor::{erase_in_line, erase_in_page, AreaPosition};
+///
+/// // Erases from the cursor to the end of the line : `\x1b[0K`
+/// print!("{}", erase_in_line(AreaPosition::AfterCursor));
+/// // Erases from the start of the page up to the cursor : `\x1b[1J`
+/// print!("{}", erase_in_page(AreaPosition::BeforeCursor));
+/// // Erases the whole page : `\x1b[2J`
+/// print!("{}", erase_in_page(AreaPosition::Whole));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum AreaPosition {
+    /// Parameter value 0 : from the active position, included, to the end.
     AfterCursor,
+    /// Parameter value 1 : from the beginning up to the active position, included.
     BeforeCursor,
+    /// Parameter value 2 : all the character positions.
     Whole,
 }
 
+impl AreaPosition {
+    /// Describes the erased character positions.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            AreaPosition::AfterCursor => "from the active position, included, to the end",
+            AreaPosition::BeforeCursor => "from the beginning up to the active position, included",
+            AreaPosition::Whole => "all the character positions",
+        }
+    }
+}
+
+impl TryFrom<u8> for AreaPosition {
+    type Error = InvalidAreaPosition;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AreaPosition::AfterCursor),
+            1 => Ok(AreaPosition::BeforeCursor),
+            2 => Ok(AreaPosition::Whole),
+            _ => Err(InvalidAreaPosition(value)),
+        }
+    }
+}
+
+/// The error returned when converting a parameter value other than 0, 1 or 2 into an [AreaPosition].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidAreaPosition(pub u8);
+
+impl Display for InvalidAreaPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid area position parameter value {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidAreaPosition {}
+
 impl Display for AreaPosition {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Self::AfterCursor => "0",
             Self::BeforeCursor => "1",
@@ -203,6 +261,32 @@ pub fn erase_in_page(area_position: AreaPosition) -> ControlSequence {
     ControlSequence::new(&[&area_position.to_string()], "J")
 }
 
+/// # ED - Erase in page
+///
+/// Erases from the active position to the end of the page, same as `erase_in_page(AreaPosition::AfterCursor)`.
+pub fn clear_from_cursor() -> ControlSequence {
+    erase_in_page(AreaPosition::AfterCursor)
+}
+
+/// Erases from the active position to the end of the page, then writes each line followed by CR LF.
+///
+/// This is the usual way for a shell to redraw a multi-line prompt, the cursor being first moved to its start.
+///
+/// ### Example
+/// ```
+/// use coded_chars::editor::redraw_below;
+///
+/// assert_eq!(redraw_below(&["$ ls", "> "]), "\x1b[0J$ ls\r\n> \r\n");
+/// ```
+pub fn redraw_below(lines: &[&str]) -> String {
+    let mut output = clear_from_cursor().to_string();
+    for line in lines {
+        output.push_str(line);
+        output.push_str("\r\n");
+    }
+    output
+}
+
 /// # EF - Erase in field
 ///
 /// If the DEVICE COMPONENT SELECT MODE (DCSM) is set to PRESENTATION, EF causes some or
@@ -273,7 +357,7 @@ pub enum EditingExtent {
 }
 
 impl Display for EditingExtent {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             EditingExtent::Page => "0",
             EditingExtent::Line => "1",
@@ -282,4 +366,31 @@ impl Display for EditingExtent {
             EditingExtent::Relevant => "4",
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::editor::{clear_from_cursor, erase_in_line, insert_line, redraw_below, AreaPosition, InvalidAreaPosition};
+
+    #[test]
+    fn insert_line_final() {
+        assert_eq!(insert_line(2).to_string(), "\x1b[2L");
+        assert_ne!(insert_line(0).to_string(), erase_in_line(AreaPosition::AfterCursor).to_string());
+    }
+
+    #[test]
+    fn redraw_prompt() {
+        assert_eq!(clear_from_cursor().to_string(), "\x1b[0J");
+        assert_eq!(redraw_below(&["first", "second"]), "\x1b[0Jfirst\r\nsecond\r\n");
+        assert_eq!(redraw_below(&[]), "\x1b[0J");
+    }
+
+    #[test]
+    fn area_position_from_parameter() {
+        assert_eq!(AreaPosition::try_from(0), Ok(AreaPosition::AfterCursor));
+        assert_eq!(AreaPosition::try_from(1), Ok(AreaPosition::BeforeCursor));
+        assert_eq!(AreaPosition::try_from(2), Ok(AreaPosition::Whole));
+        assert_eq!(AreaPosition::try_from(3), Err(InvalidAreaPosition(3)));
+        assert_eq!(AreaPosition::Whole.describe(), "all the character positions");
+    }
+}
@@ -2,22 +2,171 @@
 //!
 //! The [EscapeSequence] struct is [Display]able.
 
-use std::fmt::{Display, Formatter};
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
 use crate::introducers::ESC;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct EscapeSequence(char);
 
 impl EscapeSequence {
     pub const fn new(with: char) -> Self { Self(with) }
+
+    /// Returns the final character of this escape sequence, the one following **ESC**.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::escape::EscapeSequence;
+    /// use coded_chars::format::{NEL, RI};
+    ///
+    /// const FINAL: char = RI.as_char();
+    /// assert_eq!(FINAL, 'M');
+    /// assert!(matches!(NEL.final_byte(), 'E'));
+    /// ```
+    pub const fn as_char(&self) -> char { self.0 }
+
+    /// Returns the final byte of this escape sequence, same as [EscapeSequence::as_char].
+    pub const fn final_byte(&self) -> char { self.0 }
+
+    /// Creates an escape sequence, checking that `with` is a valid final byte.
+    ///
+    /// The final byte of an escape sequence must be in the range `0x30..=0x7E` (see ECMA-35), any other
+    /// character returns an [InvalidEscape] error.
+    pub fn try_new(with: char) -> Result<Self, InvalidEscape> {
+        match with {
+            '\x30'..='\x7E' => Ok(Self(with)),
+            _ => Err(InvalidEscape(with)),
+        }
+    }
+
+    /// Returns the mnemonic of this escape sequence if it is one of the constants of this crate, like `"RI"`
+    /// for [crate::format::RI].
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::escape::EscapeSequence;
+    ///
+    /// let sequence: EscapeSequence = "\x1bM".parse().unwrap();
+    /// assert_eq!(sequence.name(), Some("RI"));
+    /// ```
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self.0 {
+            '7' => "DECSC",
+            '8' => "DECRC",
+            '@' => "PAD",
+            'A' => "HOP",
+            'B' => "BPH",
+            'C' => "NBH",
+            'D' => "IND",
+            'E' => "NEL",
+            'F' => "SSA",
+            'G' => "ESA",
+            'H' => "HTS",
+            'I' => "HTJ",
+            'J' => "VTS",
+            'K' => "PLD",
+            'L' => "PLU",
+            'M' => "RI",
+            'N' => "SS2",
+            'O' => "SS3",
+            'P' => "DCS",
+            'Q' => "PU1",
+            'R' => "PU2",
+            'S' => "STS",
+            'T' => "CCH",
+            'U' => "MW",
+            'V' => "SPA",
+            'W' => "EPA",
+            'X' => "SOS",
+            'Y' => "SGC",
+            'Z' => "SCI",
+            '[' => "CSI",
+            '\\' => "ST",
+            ']' => "OSC",
+            '^' => "PM",
+            '_' => "APC",
+            '`' => "DMI",
+            'a' => "INT",
+            'b' => "EMI",
+            'c' => "RIS",
+            'd' => "CMD",
+            'n' => "LS2",
+            'o' => "LS3",
+            '|' => "LS3R",
+            '}' => "LS2R",
+            '~' => "LS1R",
+            _ => return None,
+        })
+    }
+
+    /// Returns the printed sequence, same as `to_string` but naming the intent.
+    pub fn to_sequence_string(&self) -> String { self.to_string() }
+
+    /// Writes the current sequence to `w`, without flushing it.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        write!(w, "{}", self)
+    }
+}
+
+/// The error returned by [EscapeSequence::try_new] when a character cannot end an escape sequence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidEscape(pub char);
+
+impl Display for InvalidEscape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid escape sequence final byte {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidEscape {}
+
+/// The error returned when parsing an [EscapeSequence] from a string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseEscapeError {
+    /// The string is not **ESC** followed by a single character.
+    NotAnEscape,
+    /// The character following **ESC** cannot end an escape sequence.
+    InvalidFinal(InvalidEscape),
+}
+
+impl Display for ParseEscapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseEscapeError::NotAnEscape => write!(f, "not an escape sequence"),
+            ParseEscapeError::InvalidFinal(invalid) => write!(f, "{}", invalid),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseEscapeError {}
+
+impl FromStr for EscapeSequence {
+    type Err = ParseEscapeError;
+
+    /// Parses **ESC** followed by a single final byte, see [EscapeSequence::try_new].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(ESC), Some(with), None) => Self::try_new(with).map_err(ParseEscapeError::InvalidFinal),
+            _ => Err(ParseEscapeError::NotAnEscape),
+        }
+    }
 }
 
 impl Display for EscapeSequence {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}{}", ESC, self.0)
     }
 }
 
+impl From<EscapeSequence> for String {
+    fn from(sequence: EscapeSequence) -> Self { sequence.to_string() }
+}
+
 pub const fn escape(c:char) -> EscapeSequence { EscapeSequence::new(c) }
 
 /// Padding character
@@ -46,3 +195,58 @@ pub const MW: EscapeSequence = escape('U');
 
 /// Single graphic character introducer
 pub const SGC: EscapeSequence = escape('Y');
+
+
+#[cfg(test)]
+mod tests {
+    use crate::escape::{EscapeSequence, InvalidEscape, ParseEscapeError};
+    use crate::format::{HTS, NEL, RI};
+    use crate::shifts::LS3R;
+
+    #[test]
+    fn try_new() {
+        assert_eq!(EscapeSequence::try_new('M').map(|e| e.to_string()), Ok("\x1bM".to_string()));
+        assert_eq!(EscapeSequence::try_new('\x07').map(|e| e.to_string()), Err(InvalidEscape('\x07')));
+        assert_eq!(EscapeSequence::try_new(' ').map(|e| e.to_string()), Err(InvalidEscape(' ')));
+    }
+
+    #[test]
+    fn parse_and_name() {
+        let parsed = RI.to_string().parse::<EscapeSequence>().unwrap();
+        assert_eq!(parsed.to_string(), RI.to_string());
+        assert_eq!(parsed.name(), Some("RI"));
+        assert_eq!(LS3R.name(), Some("LS3R"));
+        assert_eq!(EscapeSequence::new('1').name(), None);
+        assert_eq!("M".parse::<EscapeSequence>().err(), Some(ParseEscapeError::NotAnEscape));
+        assert_eq!("\x1bMM".parse::<EscapeSequence>().err(), Some(ParseEscapeError::NotAnEscape));
+        assert_eq!("\x1b\x07".parse::<EscapeSequence>().err(), Some(ParseEscapeError::InvalidFinal(InvalidEscape('\x07'))));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_to_buffer() {
+        let mut buffer = vec![];
+        EscapeSequence::new('7').write_to(&mut buffer).unwrap();
+        assert_eq!(buffer, b"\x1b7");
+    }
+
+    #[test]
+    fn final_in_const_context() {
+        const NEL_FINAL: char = NEL.as_char();
+        const HTS_FINAL: char = HTS.final_byte();
+        let name = |sequence: EscapeSequence| match sequence.as_char() {
+            NEL_FINAL => "NEL",
+            HTS_FINAL => "HTS",
+            _ => "other",
+        };
+        assert_eq!(name(NEL), "NEL");
+        assert_eq!(name(HTS), "HTS");
+        assert_eq!(name(RI), "other");
+    }
+
+    #[test]
+    fn into_string() {
+        assert_eq!(String::from(RI), "\x1bM");
+        assert_eq!(NEL.to_sequence_string(), "\x1bE");
+    }
+}
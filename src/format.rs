@@ -1,7 +1,11 @@
 //! These control functions change the format.
 
-use std::fmt::{Display, Formatter};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use core::fmt::{Display, Formatter};
 use crate::control::ControlSequence;
+use crate::cursor::{down, tabulation_control};
 use crate::escape::{escape, EscapeSequence};
 
 /// # Backspace
@@ -230,7 +234,7 @@ pub enum TabulationControl {
 }
 
 impl Display for TabulationControl {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             TabulationControl::Character => "0",
             TabulationControl::Line => "1",
@@ -243,6 +247,78 @@ impl Display for TabulationControl {
     }
 }
 
+/// A list of character tabulation stops, by column.
+///
+/// ### Example
+/// ```
+/// use coded_chars::format::TabStops;
+///
+/// // Clears the current stops before setting new ones.
+/// print!("{}{}", TabStops::clear_all(), TabStops::new(&[8, 16, 24]).set());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TabStops {
+    columns: Vec<usize>,
+}
+
+impl TabStops {
+    pub fn new(columns: &[usize]) -> Self { Self { columns: columns.to_vec() } }
+
+    /// The columns of the stops.
+    pub fn columns(&self) -> &[usize] { &self.columns }
+
+    /// Returns the stream setting a stop at each column, moving there with HPA before each HTS.
+    ///
+    /// The active presentation position is left on the last stop.
+    pub fn set(&self) -> String {
+        self.columns.iter()
+            .map(|column| format!("{}{}", character_absolute(*column), HTS))
+            .collect()
+    }
+
+    /// # CTC - Clears all the character tabulation stops
+    pub fn clear_all() -> ControlSequence {
+        tabulation_control(TabulationControl::CharacterClearAll)
+    }
+}
+
+/// Returns `text` imaged as a superscript, between PLU and PLD, the baseline being restored afterward.
+///
+/// An empty `text` returns an empty string.
+///
+/// ### Example
+/// ```
+/// use coded_chars::format::superscript;
+///
+/// assert_eq!(format!("x{}", superscript("2")), "x\x1bL2\x1bK");
+/// ```
+pub fn superscript(text: &str) -> String {
+    if text.is_empty() { String::new() } else { format!("{}{}{}", PLU, text, PLD) }
+}
+
+/// Returns `text` imaged as a subscript, between PLD and PLU, the baseline being restored afterward.
+///
+/// An empty `text` returns an empty string.
+pub fn subscript(text: &str) -> String {
+    if text.is_empty() { String::new() } else { format!("{}{}{}", PLD, text, PLU) }
+}
+
+/// Returns a fraction, the `numerator` raised as a [superscript] and the `denominator` lowered as a [subscript]
+/// around a solidus.
+///
+/// ### Note
+/// PLD and PLU are mostly implemented by printers, terminal emulators usually ignore them.
+///
+/// ### Example
+/// ```
+/// use coded_chars::format::fraction;
+///
+/// assert_eq!(fraction("1", "2"), "\x1bL1\x1bK/\x1bK2\x1bL");
+/// ```
+pub fn fraction(numerator: &str, denominator: &str) -> String {
+    format!("{}/{}", superscript(numerator), subscript(denominator))
+}
+
 /// # TSR - Tabulation stop remove
 ///
 /// TSR causes any character tabulation stop at character position n in the active line (the line that contains
@@ -268,4 +344,67 @@ pub fn line_backward(n: usize) -> ControlSequence { ControlSequence::new(&[&n.to
 ///
 /// VPR causes the active data position to be moved by n line positions in the data component in a direction
 /// parallel to the line progression.
-pub fn line_forward(n: usize) -> ControlSequence { ControlSequence::new(&[&n.to_string()], "e") }
\ No newline at end of file
+pub fn line_forward(n: usize) -> ControlSequence { ControlSequence::new(&[&n.to_string()], "e") }
+
+/// The ways of moving to the following line, see [line_break].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineBreak {
+    /// LINE FEED ([LF]) : moves to the following line, scrolling the page up from the last line of the
+    /// scrolling region. Whether the cursor also returns to the first column depends on the LINE FEED/NEW
+    /// LINE MODE (LNM), most terminals keep the column.
+    Scroll,
+    /// NEXT LINE ([NEL]) : moves to the first column of the following line, scrolling like LF.
+    NextLineHome,
+    /// CURSOR DOWN (CUD 1) : moves to the following line in the same column, without scrolling : the cursor
+    /// stays on the last line of the scrolling region.
+    CursorDown,
+}
+
+/// Returns the control function moving to the following line the way `kind` specifies.
+///
+/// ### Example
+/// ```
+/// use coded_chars::format::{line_break, LineBreak};
+///
+/// print!("Loading...{}", line_break(LineBreak::NextLineHome));
+/// ```
+pub fn line_break(kind: LineBreak) -> String {
+    match kind {
+        LineBreak::Scroll => LF.to_string(),
+        LineBreak::NextLineHome => NEL.to_string(),
+        LineBreak::CursorDown => down(1).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{fraction, line_break, subscript, superscript, LineBreak, TabStops, PLD, PLU};
+
+    #[test]
+    fn line_breaks() {
+        assert_eq!(line_break(LineBreak::Scroll), "\n");
+        assert_eq!(line_break(LineBreak::NextLineHome), "\x1bE");
+        assert_eq!(line_break(LineBreak::CursorDown), "\x1b[1B");
+    }
+
+    #[test]
+    fn tab_stops() {
+        assert_eq!(TabStops::new(&[8, 16, 24]).set(), "\x1b[8`\x1bH\x1b[16`\x1bH\x1b[24`\x1bH");
+        assert_eq!(TabStops::default().set(), "");
+        assert_eq!(TabStops::clear_all().to_string(), "\x1b[5W");
+    }
+
+    #[test]
+    fn partial_lines() {
+        assert_eq!(superscript("2"), "\x1bL2\x1bK");
+        assert_eq!(subscript("i"), "\x1bKi\x1bL");
+        assert_eq!(superscript(""), "");
+        assert_eq!(subscript(""), "");
+    }
+
+    #[test]
+    fn fraction_structure() {
+        assert_eq!(fraction("3", "4"), format!("{}3{}/{}4{}", PLU, PLD, PLD, PLU));
+        assert_eq!(fraction("", "4"), format!("/{}4{}", PLD, PLU));
+    }
+}
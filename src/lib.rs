@@ -10,14 +10,16 @@
 //!
 //! ## An example : format a text printed in an ECMA/ANSI terminal
 //! ```
+//! # #[cfg(feature = "std")]
 //! use coded_chars::clear_screen;
 //! use coded_chars::cursor::set_position;
 //! use coded_chars::presentation::{format_str, select_graphic};
 //!
 //! // Direct format
-//! println!("Hello {}World{} !", select_graphic().fg_red().bold().underline(), select_graphic().default());
+//! println!("Hello {}World{} !", select_graphic().fg_red().bold().underline(), select_graphic().default_rendition());
 //!
 //! // Clear screen
+//! # #[cfg(feature = "std")]
 //! clear_screen();
 //!
 //! // Using format_str
@@ -27,6 +29,7 @@
 //!  );
 //! println!("Hello {} !", formatted);
 //!
+//! # #[cfg(feature = "std")]
 //! set_position(5, 1).exec();
 //! println!("This line is printed on the fifth line.");
 //! ```
@@ -119,7 +122,7 @@
 //!     - STAB : [presentation::select_tabulation]
 //!     - SVS : [presentation::select_line_spacing]
 //!     - TAC : [presentation::align_center]
-//!     - TALE : [presentation::align_trailing]
+//!     - TALE : [presentation::align_leading]
 //!     - TATE : [presentation::align_trailing]
 //!     - TCC : [presentation::tabulation_center_on_char]
 //!     - TSS : [presentation::specify_thin_space]
@@ -209,6 +212,10 @@
 //! - STS : [escape::STS]
 //! - SUB : [characters::SUB]
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub mod characters;
 pub mod escape;
 pub mod delimiters;
@@ -224,21 +231,79 @@ pub mod device;
 pub mod area;
 pub mod mode;
 pub mod cursor;
+pub mod parser;
+pub mod text;
+pub mod screen;
+pub mod mouse;
+pub mod osc;
+pub mod render;
+pub mod window;
+pub mod output;
+pub mod sgr;
+pub mod widgets;
+#[cfg(all(feature = "std", any(test, feature = "test-terminal")))]
+pub mod terminal;
+
+pub use text::{display_width, strip_sequences};
 
 /// The page is erased and the cursor position is set to the first line and the first column.
 ///
 /// - The ANSI/ECMA printed function is : `ED(2),CUP(1,1)`
 /// - The ANSI/ECMA printed sequence is : `\x1b[2J\x1b[1;1H`
 /// 
+#[cfg(feature = "std")]
 pub fn clear_screen() {
+    print!("{}", clear_screen_seq());
+}
+
+/// Returns the sequence printed by [clear_screen], to compose it with other output.
+///
+/// ### Example
+/// ```
+/// use coded_chars::clear_screen_seq;
+///
+/// let mut buffer = String::new();
+/// buffer.push_str(&clear_screen_seq());
+/// assert_eq!(buffer, "\x1b[2J\x1b[1;1H");
+/// ```
+pub fn clear_screen_seq() -> alloc::string::String {
     use crate::cursor::set_position;
     use crate::editor::{erase_in_page, AreaPosition};
-    
-    print!("{}{}", erase_in_page(AreaPosition::Whole), set_position(1, 1));
+
+    alloc::format!("{}{}", erase_in_page(AreaPosition::Whole), set_position(1, 1))
+}
+
+/// The active line is erased and the cursor position is set to its first column.
+///
+/// - The ANSI/ECMA printed function is : `EL(2),HPA(1)`
+/// - The ANSI/ECMA printed sequence is : `` \x1b[2K\x1b[1` ``
+#[cfg(feature = "std")]
+pub fn clear_line() {
+    print!("{}", clear_line_seq());
+}
+
+/// Returns the sequence printed by [clear_line], to compose it with other output.
+///
+/// ### Example
+/// ```
+/// use coded_chars::clear_line_seq;
+///
+/// let progress = format!("{}[####      ] 40%", clear_line_seq());
+/// assert!(progress.starts_with("\x1b[2K\x1b[1`"));
+/// ```
+pub fn clear_line_seq() -> alloc::string::String {
+    use crate::editor::{erase_in_line, AreaPosition};
+    use crate::format::character_absolute;
+
+    alloc::format!("{}{}", erase_in_line(AreaPosition::Whole), character_absolute(1))
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    #[cfg(feature = "std")]
     #[test]
     fn test() {
         use crate::clear_screen;
@@ -246,7 +311,7 @@ mod tests {
         use crate::presentation::{format_str, select_graphic};
 
         // Direct format
-        println!("Hello {}World{} !", select_graphic().fg_red().bold().underline(), select_graphic().default());
+        println!("Hello {}World{} !", select_graphic().fg_red().bold().underline(), select_graphic().default_rendition());
 
         // Clear screen
         clear_screen();
@@ -262,4 +327,140 @@ mod tests {
         println!("This line is printed on the fifth line.");
     }
 
+    /// Smoke test of the builders available without `std`, run with `cargo test --no-default-features`.
+    #[test]
+    fn alloc_only_builders() {
+        use crate::control::Sequence;
+        use crate::cursor::set_position;
+        use crate::presentation::{format_str, select_graphic};
+
+        let output = Sequence::new()
+            .push(set_position(2, 3))
+            .text(&format_str("ok", select_graphic().fg_green()))
+            .build();
+        assert_eq!(output, "\x1b[2;3H\x1b[32mok\x1b[0m");
+        assert_eq!(crate::strip_sequences(&output), "ok");
+    }
+
+    #[test]
+    fn clear_line_sequence() {
+        assert_eq!(crate::clear_line_seq(), "\x1b[2K\x1b[1`");
+    }
+
+    #[test]
+    fn clear_screen_sequence() {
+        assert_eq!(crate::clear_screen_seq(), "\x1b[2J\x1b[1;1H");
+    }
+
+    fn params<T: core::fmt::Display>(values: &[T]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn range(from: usize, to: usize) -> Vec<String> {
+        (from..=to).map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn parameter_enums_match_standard() {
+        use crate::area::Qualification;
+        use crate::device::{ControlString, CopyStatus, StatusReport};
+        use crate::editor::{AreaPosition, EditingExtent};
+        use crate::format::TabulationControl;
+        use crate::presentation::*;
+
+        assert_eq!(params(&[
+            Font::Primary, Font::Alternative1, Font::Alternative2, Font::Alternative3, Font::Alternative4,
+            Font::Alternative5, Font::Alternative6, Font::Alternative7, Font::Alternative8, Font::Alternative9,
+        ]), range(0, 9));
+        assert_eq!(params(&[Combination::Two, Combination::Start, Combination::End]), range(0, 2));
+        assert_eq!(params(&[
+            JustifyMode::None, JustifyMode::WordFill, JustifyMode::WordSpace, JustifyMode::LetterSpace,
+            JustifyMode::Hyphen, JustifyMode::FlushHome, JustifyMode::Center, JustifyMode::FlushLimit,
+            JustifyMode::ItalianHyphen,
+        ]), range(0, 8));
+        assert_eq!(params(&[Expansion::Normal, Expansion::Expanded, Expansion::Condensed]), range(0, 2));
+        assert_eq!(params(&[
+            PageFormat::TallText, PageFormat::WideText, PageFormat::TallA4, PageFormat::WideA4,
+            PageFormat::TallLetter, PageFormat::WideLetter, PageFormat::TallExtA4, PageFormat::WideExtA4,
+            PageFormat::TallLegal, PageFormat::WideLegal, PageFormat::A4ShortLines, PageFormat::A4LongLines,
+            PageFormat::B5ShortLines, PageFormat::B5LongLines, PageFormat::B4ShortLines, PageFormat::B4LongLines,
+        ]), range(0, 15));
+        assert_eq!(params(&[
+            TextDelimiter::End, TextDelimiter::BeginPrincipal, TextDelimiter::BeginSupplementary,
+            TextDelimiter::BeginSupplementaryPhoneticJapanese, TextDelimiter::BeginSupplementaryPhoneticChinese,
+            TextDelimiter::EndPhonetic,
+        ]), range(0, 5));
+        assert_eq!(params(&[
+            Layout::FlushHome, Layout::FlushHomeAndFill, Layout::Center, Layout::CenterAndFill,
+            Layout::FlushLimit, Layout::FlushLimitAndFill, Layout::FlushBoth,
+        ]), range(0, 6));
+        assert_eq!(params(&[
+            Orientation::North, Orientation::NorthWest, Orientation::West, Orientation::SouthWest,
+            Orientation::South, Orientation::SouthEast, Orientation::East, Orientation::NorthEast,
+        ]), range(0, 7));
+        assert_eq!(params(&[CharacterPath::LeftToRight, CharacterPath::RightToLeft]), range(1, 2));
+        assert_eq!(params(&[PathEffect::Undefined, PathEffect::UpdatePresentation, PathEffect::UpdateData]), range(0, 2));
+        assert_eq!(params(&[StringDirection::End, StringDirection::StartLeftToRight, StringDirection::StartRightToLeft]), range(0, 2));
+        assert_eq!(params(&[MovementDirection::Same, MovementDirection::Opposite]), range(0, 1));
+        assert_eq!(params(&[
+            CharacterSpacing::Per25mm10Chars, CharacterSpacing::Per25mm12Chars, CharacterSpacing::Per25mm15Chars,
+            CharacterSpacing::Per25mm16Chars, CharacterSpacing::Per25mm3Chars, CharacterSpacing::Per50mm9Chars,
+            CharacterSpacing::Per25mm4Chars,
+        ]), range(0, 6));
+        assert_eq!(params(&[PrintQuality::Highest, PrintQuality::Medium, PrintQuality::Draft]), range(0, 2));
+        assert_eq!(params(&[StringReversion::End, StringReversion::BeginReverse]), range(0, 1));
+        assert_eq!(params(&[
+            SizeUnit::Character, SizeUnit::Millimeter, SizeUnit::ComputerDeciPoint, SizeUnit::DeciDidot,
+            SizeUnit::Mil, SizeUnit::BasicMeasuringUnit, SizeUnit::Micrometer, SizeUnit::Pixel, SizeUnit::DeciPoint,
+        ]), range(0, 8));
+        assert_eq!(params(&[
+            LineSpacing::Per25mm6Lines, LineSpacing::Per25mm4Lines, LineSpacing::Per25mm3Lines,
+            LineSpacing::Per25mm12Lines, LineSpacing::Per25mm8Lines, LineSpacing::Per30mm6Lines,
+            LineSpacing::Per30mm4Lines, LineSpacing::Per30mm3Lines, LineSpacing::Per30mm12Lines,
+            LineSpacing::Per25mm2Lines,
+        ]), range(0, 9));
+        assert_eq!(params(&[
+            TabulationControl::Character, TabulationControl::Line, TabulationControl::CharacterRemove,
+            TabulationControl::LineRemove, TabulationControl::CharacterClearLine,
+            TabulationControl::CharacterClearAll, TabulationControl::LineClearAll,
+        ]), range(0, 6));
+        assert_eq!(params(&[AreaPosition::AfterCursor, AreaPosition::BeforeCursor, AreaPosition::Whole]), range(0, 2));
+        assert_eq!(params(&[
+            EditingExtent::Page, EditingExtent::Line, EditingExtent::Field, EditingExtent::QualifiedArea,
+            EditingExtent::Relevant,
+        ]), range(0, 4));
+        assert_eq!(params(&[
+            StatusReport::Ready, StatusReport::BusyRetry, StatusReport::BusyWaiting, StatusReport::ErrorRetry,
+            StatusReport::ErrorWaiting, StatusReport::MessageWaiting, StatusReport::PositionWaiting,
+        ]), range(0, 6));
+        assert_eq!(params(&[ControlString::SRTMDiagnose, ControlString::Ecma35DCRS]), range(1, 2));
+        assert_eq!(params(&[
+            CopyStatus::InitTo1, CopyStatus::InitFrom1, CopyStatus::InitTo2, CopyStatus::InitFrom2,
+            CopyStatus::Stop1, CopyStatus::Start1, CopyStatus::Stop2, CopyStatus::Start2,
+        ]), range(0, 7));
+        assert_eq!(params(&[
+            Qualification::UnprotectNoGuard, Qualification::ProtectGuard, Qualification::Character,
+            Qualification::Numeric, Qualification::Alphabet, Qualification::AlignLast, Qualification::FillZero,
+            Qualification::SetTabStop, Qualification::Protect, Qualification::FillSpace, Qualification::AlignFirst,
+            Qualification::Reverse,
+        ]), range(0, 11));
+    }
+
+    #[test]
+    fn final_bytes_are_unique() {
+        use crate::cursor::Direction;
+        use crate::display::ScrollDirection;
+
+        let mut finals = params(&[
+            Direction::Up, Direction::Down, Direction::Forward, Direction::Backward,
+            Direction::NextLine, Direction::PreviousLine,
+        ]);
+        finals.extend(params(&[
+            ScrollDirection::Down, ScrollDirection::Left, ScrollDirection::Right, ScrollDirection::Up,
+        ]));
+        let count = finals.len();
+        finals.sort();
+        finals.dedup();
+        assert_eq!(finals.len(), count);
+    }
 }
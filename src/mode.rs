@@ -1,5 +1,8 @@
 //! This module helps create the CSI sequences for `SM` and `RM`.
 
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::vec;
 use crate::control::ControlSequence;
 
 /// A struct representing an `SM` or an `RM` CSI function.
@@ -14,6 +17,7 @@ use crate::control::ControlSequence;
 /// // Reset a mode (IMPLICIT-BDSM mode)
 /// println!("{}", mode().bi_directional_support().reset());
 /// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Mode {
     modes: Vec<String>,
 }
@@ -289,6 +293,11 @@ impl Mode {
     /// Control function affected is SGR : [crate::presentation::select_graphic].
     pub fn graphic_rendition_combination(&mut self) -> &mut Self { self.add("21") }
 
+    /// Returns an iterator over the parameters of this sequence, in order.
+    pub fn params_iter(&self) -> impl Iterator<Item = &str> {
+        self.modes.iter().map(String::as_str)
+    }
+
     /// # SM - Set Mode
     /// SM causes the modes of the receiving device to be set as specified.
     pub fn set(&self) -> ControlSequence {
@@ -306,6 +315,15 @@ impl Mode {
     }
 }
 
+impl<'a> IntoIterator for &'a Mode {
+    type Item = &'a str;
+    type IntoIter = core::iter::Map<core::slice::Iter<'a, String>, fn(&'a String) -> &'a str>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.modes.iter().map(String::as_str as fn(&'a String) -> &'a str)
+    }
+}
+
 /// Creates a new [Mode] sequence to set or reset devices modes.
 ///
 /// ### Example
@@ -313,6 +331,87 @@ impl Mode {
 /// use coded_chars::mode::mode;
 ///
 /// // Sets the DCSM mode to PRESENTATION and the HEM mode to FOLLOWING.
+/// # #[cfg(feature = "std")]
 /// mode().device_component_select().character_editing().set().exec();
 /// ```
-pub fn mode() -> Mode { Mode::new() }
\ No newline at end of file
+pub fn mode() -> Mode { Mode::new() }
+
+/// A batch of modes to set or reset, emitted as at most one `SM` and one `RM` sequence.
+///
+/// When a mode is recorded twice, the last target wins. The sequence holding the first recorded mode is
+/// emitted first, the modes keeping their recording order within each sequence.
+///
+/// ### Example
+/// ```
+/// use coded_chars::mode::{mode, ModeBatch};
+///
+/// let batch = ModeBatch::new()
+///     .with(mode().device_component_select(), true)
+///     .with(mode().character_editing(), false)
+///     .build();
+/// assert_eq!(batch, "\x1b[9h\x1b[10l");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModeBatch {
+    modes: Vec<(String, bool)>,
+}
+
+impl ModeBatch {
+    pub fn new() -> Self { Self::default() }
+
+    /// Records that the modes of `modes` must be set if `desired` is `true`, reset otherwise.
+    pub fn with(&mut self, modes: &Mode, desired: bool) -> &mut Self {
+        for code in modes {
+            self.modes.retain(|(recorded, _)| recorded != code);
+            self.modes.push((code.to_string(), desired));
+        }
+        self
+    }
+
+    /// Returns the `SM` and `RM` sequences applying the recorded modes.
+    pub fn build(&self) -> String {
+        let sequence = |desired: bool, end: &str| {
+            let codes = self.modes.iter()
+                .filter(|(_, target)| *target == desired)
+                .map(|(code, _)| code.as_str())
+                .collect::<Vec<_>>();
+            if codes.is_empty() { String::new() } else { ControlSequence::new(&codes, end).to_string() }
+        };
+        let (set, reset) = (sequence(true, "h"), sequence(false, "l"));
+        match self.modes.first() {
+            Some((_, false)) => reset + &set,
+            _ => set + &reset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mode::{mode, ModeBatch};
+
+    #[test]
+    fn batch_ordering() {
+        let batch = ModeBatch::new()
+            .with(mode().device_component_select(), true)
+            .with(mode().character_editing(), false)
+            .build();
+        assert_eq!(batch, "\x1b[9h\x1b[10l");
+        let batch = ModeBatch::new()
+            .with(mode().character_editing(), false)
+            .with(mode().device_component_select(), true)
+            .build();
+        assert_eq!(batch, "\x1b[10l\x1b[9h");
+        assert_eq!(ModeBatch::new().build(), "");
+    }
+
+    #[test]
+    fn batch_coalescing() {
+        let batch = ModeBatch::new()
+            .with(mode().keyboard_action(), true)
+            .with(mode().character_editing(), false)
+            .with(mode().device_component_select().format_effector_action(), true)
+            .with(mode().keyboard_action(), false)
+            .build();
+        assert_eq!(batch, "\x1b[10;2l\x1b[9;13h");
+    }
+}
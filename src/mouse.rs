@@ -0,0 +1,72 @@
+//! This module helps enable and disable mouse tracking.
+//!
+//! Mouse tracking uses DEC private modes widely implemented by terminals, they are not part of ECMA-48.
+
+use alloc::string::ToString;
+use core::fmt::{Display, Formatter};
+use crate::control::ControlSequence;
+
+/// The mouse tracking modes.
+///
+/// [MouseMode::SgrExtended] does not enable any tracking by itself, it selects the encoding of the reports
+/// and should be combined with one of the other modes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MouseMode {
+    /// Reports button presses and releases (`?1000`).
+    Click,
+    /// Reports button presses, releases and motion while a button is held (`?1002`).
+    Drag,
+    /// Reports every motion, with or without a button held (`?1003`).
+    AnyMotion,
+    /// Encodes the reports as `CSI < b ; x ; y M` or `m` (`?1006`).
+    SgrExtended,
+}
+
+impl Display for MouseMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", match self {
+            MouseMode::Click => "1000",
+            MouseMode::Drag => "1002",
+            MouseMode::AnyMotion => "1003",
+            MouseMode::SgrExtended => "1006",
+        })
+    }
+}
+
+/// Enables the mouse tracking `mode`.
+///
+/// - The printed sequence is : `\x1b[?{mode}h`
+///
+/// ### Example
+/// ```
+/// use coded_chars::mouse::{enable, MouseMode};
+///
+/// print!("{}{}", enable(MouseMode::Drag), enable(MouseMode::SgrExtended));
+/// ```
+pub fn enable(mode: MouseMode) -> ControlSequence {
+    ControlSequence::private(&[&mode.to_string()], "h")
+}
+
+/// Disables the mouse tracking `mode`.
+///
+/// - The printed sequence is : `\x1b[?{mode}l`
+pub fn disable(mode: MouseMode) -> ControlSequence {
+    ControlSequence::private(&[&mode.to_string()], "l")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mouse::{disable, enable, MouseMode};
+
+    #[test]
+    fn mouse_modes() {
+        assert_eq!(enable(MouseMode::Click).to_string(), "\x1b[?1000h");
+        assert_eq!(enable(MouseMode::Drag).to_string(), "\x1b[?1002h");
+        assert_eq!(enable(MouseMode::AnyMotion).to_string(), "\x1b[?1003h");
+        assert_eq!(enable(MouseMode::SgrExtended).to_string(), "\x1b[?1006h");
+        assert_eq!(disable(MouseMode::Click).to_string(), "\x1b[?1000l");
+        assert_eq!(disable(MouseMode::Drag).to_string(), "\x1b[?1002l");
+        assert_eq!(disable(MouseMode::AnyMotion).to_string(), "\x1b[?1003l");
+        assert_eq!(disable(MouseMode::SgrExtended).to_string(), "\x1b[?1006l");
+    }
+}
@@ -0,0 +1,119 @@
+//! This module helps build operating system commands, like setting the window title.
+//!
+//! The command numbers are not part of ECMA-48, which leaves the content of an OSC string to the operating
+//! system, but are widely implemented by terminals.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+use crate::characters::BEL;
+use crate::delimiters::{escape_string_payload, OSC, ST};
+use crate::presentation::{format_str, GraphicSelection};
+
+/// The terminator of an OSC string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OscTerminator {
+    /// BEL, the terminator historically used by xterm.
+    Bel,
+    /// STRING TERMINATOR (ST), the terminator defined by ECMA-48.
+    St,
+}
+
+impl Display for OscTerminator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OscTerminator::Bel => write!(f, "{}", BEL),
+            OscTerminator::St => write!(f, "{}", ST),
+        }
+    }
+}
+
+/// Returns the OSC string `ps ; payload`, ended by `terminator`.
+///
+/// The characters of `payload` which would end or abort the string are removed, see
+/// [escape_string_payload].
+///
+/// ### Example
+/// ```
+/// use coded_chars::osc::{osc, OscTerminator};
+///
+/// assert_eq!(osc(2, "Title", OscTerminator::St), "\x1b]2;Title\x1b\\");
+/// ```
+pub fn osc(ps: u32, payload: &str, terminator: OscTerminator) -> String {
+    format!("{}{};{}{}", OSC, ps, escape_string_payload(payload), terminator)
+}
+
+/// Sets the window title and the icon name to `s`.
+///
+/// - The printed sequence is : `\x1b]0;{s}\x07`
+pub fn set_title(s: &str) -> String {
+    osc(0, s, OscTerminator::Bel)
+}
+
+/// Returns `text` as a clickable link to `url` (OSC 8).
+///
+/// The link is closed after `text`, which is passed through untouched.
+///
+/// ### Example
+/// ```
+/// use coded_chars::osc::hyperlink;
+///
+/// assert_eq!(
+///     hyperlink("https://example.com", "Example"),
+///     "\x1b]8;;https://example.com\x1b\\Example\x1b]8;;\x1b\\"
+/// );
+/// ```
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("{}{}{}", osc(8, &format!(";{}", url), OscTerminator::St), text, osc(8, ";", OscTerminator::St))
+}
+
+/// Same as [hyperlink], with an `id` grouping the links which point to the same target, like a link
+/// wrapped over several lines.
+pub fn hyperlink_with_id(id: &str, url: &str, text: &str) -> String {
+    format!("{}{}{}", osc(8, &format!("id={};{}", id, url), OscTerminator::St), text, osc(8, ";", OscTerminator::St))
+}
+
+/// Same as [hyperlink], with `text` printed with `style`.
+///
+/// The style is reset before the link is closed, so it neither bleeds past the link nor depends on how the
+/// terminal handles a rendition change across a link boundary.
+///
+/// ### Example
+/// ```
+/// use coded_chars::osc::styled_hyperlink;
+/// use coded_chars::presentation::select_graphic;
+///
+/// println!("{}", styled_hyperlink("https://example.com", "Example", select_graphic().fg_blue().underline()));
+/// ```
+pub fn styled_hyperlink(url: &str, text: &str, style: &GraphicSelection) -> String {
+    hyperlink(url, &format_str(text, style))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::osc::{hyperlink, hyperlink_with_id, osc, set_title, styled_hyperlink, OscTerminator};
+    use crate::presentation::select_graphic;
+
+    #[test]
+    fn terminators() {
+        assert_eq!(set_title("Hello"), "\x1b]0;Hello\x07");
+        assert_eq!(osc(0, "Hello", OscTerminator::Bel), "\x1b]0;Hello\x07");
+        assert_eq!(osc(0, "Hello", OscTerminator::St), "\x1b]0;Hello\x1b\\");
+        assert_eq!(set_title("a\x07b\x1b\\c"), "\x1b]0;ab\\c\x07");
+    }
+
+    #[test]
+    fn hyperlinks() {
+        let link = hyperlink("https://example.com", "\x1b[1mbold\x1b[0m");
+        assert_eq!(link, "\x1b]8;;https://example.com\x1b\\\x1b[1mbold\x1b[0m\x1b]8;;\x1b\\");
+        assert!(link.ends_with("\x1b]8;;\x1b\\"));
+        assert_eq!(hyperlink_with_id("a1", "https://example.com", "x"), "\x1b]8;id=a1;https://example.com\x1b\\x\x1b]8;;\x1b\\");
+    }
+
+    #[test]
+    fn styled_link_nesting() {
+        let link = styled_hyperlink("https://example.com", "docs", select_graphic().fg_blue().underline());
+        assert_eq!(link, "\x1b]8;;https://example.com\x1b\\\x1b[34;4mdocs\x1b[0m\x1b]8;;\x1b\\");
+        assert!(link.find("\x1b[0m") < link.rfind("\x1b]8;;"));
+    }
+}
@@ -0,0 +1,127 @@
+//! This module helps write code emitting control functions against a [Terminal] trait.
+//!
+//! A program written against [Terminal] prints to the standard output with a [StdoutTerminal], and its
+//! output can be asserted in tests with a [RecordingTerminal].
+//!
+//! ### Example
+//! ```
+//! use coded_chars::cursor::Position;
+//! use coded_chars::output::{RecordingTerminal, Terminal};
+//! use coded_chars::presentation::select_graphic;
+//!
+//! fn greet(terminal: &mut impl Terminal) {
+//!     terminal.clear();
+//!     terminal.move_to(Position { line: 2, column: 4 });
+//!     terminal.set_style(select_graphic().bold());
+//!     terminal.write("Hello");
+//!     terminal.reset_style();
+//! }
+//!
+//! let mut terminal = RecordingTerminal::new();
+//! greet(&mut terminal);
+//! assert_eq!(terminal.writes()[1], "\x1b[2;4H");
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::clear_screen_seq;
+use crate::cursor::{move_to, Position};
+use crate::presentation::{GraphicSelection, RESET};
+
+/// A destination of control functions and text.
+///
+/// Only [Terminal::write] must be implemented, the other methods write the corresponding sequences with it.
+pub trait Terminal {
+    /// Writes `s`, text or control functions.
+    fn write(&mut self, s: &str);
+
+    /// Sets the cursor position, see [move_to].
+    fn move_to(&mut self, position: Position) {
+        self.write(&move_to(position).to_string())
+    }
+
+    /// Sets the graphic rendition of the following text.
+    fn set_style(&mut self, style: &GraphicSelection) {
+        self.write(&style.to_string())
+    }
+
+    /// Resets the graphic rendition to the default rendition ([RESET]).
+    fn reset_style(&mut self) {
+        self.write(RESET)
+    }
+
+    /// Erases the page and moves the cursor to the first line and the first column, see [clear_screen_seq].
+    fn clear(&mut self) {
+        self.write(&clear_screen_seq())
+    }
+}
+
+/// A [Terminal] printing in `stdout` directly.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StdoutTerminal;
+
+#[cfg(feature = "std")]
+impl StdoutTerminal {
+    pub fn new() -> Self { Self }
+}
+
+#[cfg(feature = "std")]
+impl Terminal for StdoutTerminal {
+    fn write(&mut self, s: &str) {
+        use std::io::stdout;
+        use std::io::Write;
+
+        let mut stdout = stdout();
+        stdout.write_all(s.as_bytes()).unwrap();
+        stdout.flush().unwrap()
+    }
+}
+
+/// A [Terminal] keeping every write, in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecordingTerminal {
+    writes: Vec<String>,
+}
+
+impl RecordingTerminal {
+    pub fn new() -> Self { Self::default() }
+
+    /// Returns the writes received by this terminal, one string per call of [Terminal::write].
+    pub fn writes(&self) -> &[String] { &self.writes }
+
+    /// Returns every write received by this terminal, concatenated.
+    pub fn output(&self) -> String { self.writes.concat() }
+
+    /// Forgets the writes received so far.
+    pub fn clear_writes(&mut self) { self.writes.clear() }
+}
+
+impl Terminal for RecordingTerminal {
+    fn write(&mut self, s: &str) {
+        self.writes.push(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cursor::Position;
+    use crate::output::{RecordingTerminal, Terminal};
+    use crate::presentation::select_graphic;
+
+    #[test]
+    fn recorded_writes() {
+        let mut terminal = RecordingTerminal::new();
+        terminal.clear();
+        terminal.move_to(Position::from((3, 5)));
+        terminal.set_style(select_graphic().fg_green());
+        terminal.write("ok");
+        terminal.reset_style();
+
+        assert_eq!(terminal.writes(), ["\x1b[2J\x1b[1;1H", "\x1b[3;5H", "\x1b[32m", "ok", "\x1b[0m"]);
+        assert_eq!(terminal.output(), "\x1b[2J\x1b[1;1H\x1b[3;5H\x1b[32mok\x1b[0m");
+
+        terminal.clear_writes();
+        assert!(terminal.writes().is_empty());
+    }
+}
@@ -0,0 +1,575 @@
+//! This module helps read back the control functions found in a string.
+//!
+//! Both the 7-bit forms (introduced by **ESC**) and the 8-bit forms (single C1 characters) are recognized.
+
+#[cfg(feature = "vte")]
+use alloc::format;
+#[cfg(feature = "vte")]
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::vec;
+use core::fmt::{Display, Formatter};
+use crate::control::{AnySequence, ControlSequence};
+use crate::cursor::{move_cursor, set_position, Direction};
+use crate::display::{scroll, ScrollDirection};
+use crate::editor::{delete_char, delete_line, erase_char, erase_in_line, erase_in_page, insert_char, insert_line, AreaPosition};
+use crate::escape::EscapeSequence;
+use crate::introducers::ESC;
+use crate::presentation::GraphicSelection;
+
+/// The kind of a control function found in a string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SequenceKind {
+    /// A control sequence, introduced by CSI.
+    Csi,
+    /// An operating system command, introduced by OSC and terminated by ST or BEL.
+    Osc,
+    /// A device control string, introduced by DCS and terminated by ST.
+    Dcs,
+    /// An application program command, introduced by APC and terminated by ST.
+    Apc,
+    /// A privacy message, introduced by PM and terminated by ST.
+    Pm,
+    /// A character string, introduced by SOS and terminated by ST.
+    Sos,
+    /// Any other escape sequence, like [crate::format::NEL] or [crate::device::RIS].
+    Escape,
+    /// A single C0 or C1 control character, like [crate::characters::BEL].
+    Control,
+}
+
+/// Detects the control function at the start of `input`.
+///
+/// Returns its kind and its length in bytes, or `None` if `input` is empty or starts with a graphic character.
+///
+/// A control sequence is consumed up to its final byte, a control string up to its terminator. A function
+/// which is not terminated consumes the rest of `input`.
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::set_position;
+/// use coded_chars::parser::{classify, SequenceKind};
+///
+/// let input = format!("{}Hello", set_position(1, 1));
+/// assert_eq!(classify(&input), Some((SequenceKind::Csi, 6)));
+/// assert_eq!(classify("Hello"), None);
+/// ```
+pub fn classify(input: &str) -> Option<(SequenceKind, usize)> {
+    let mut chars = input.chars();
+    let first = chars.next()?;
+    let offset = first.len_utf8();
+    let bytes = input.as_bytes();
+
+    let introduced = match first {
+        ESC => match chars.next() {
+            Some('[') => Some(SequenceKind::Csi),
+            Some(']') => Some(SequenceKind::Osc),
+            Some('P') => Some(SequenceKind::Dcs),
+            Some('_') => Some(SequenceKind::Apc),
+            Some('^') => Some(SequenceKind::Pm),
+            Some('X') => Some(SequenceKind::Sos),
+            _ => return Some((SequenceKind::Escape, escape_len(bytes))),
+        }.map(|kind| (kind, 2)),
+        '\u{9B}' => Some((SequenceKind::Csi, offset)),
+        '\u{9D}' => Some((SequenceKind::Osc, offset)),
+        '\u{90}' => Some((SequenceKind::Dcs, offset)),
+        '\u{9F}' => Some((SequenceKind::Apc, offset)),
+        '\u{9E}' => Some((SequenceKind::Pm, offset)),
+        '\u{98}' => Some((SequenceKind::Sos, offset)),
+        c if c.is_control() => return Some((SequenceKind::Control, offset)),
+        _ => return None,
+    };
+
+    introduced.map(|(kind, from)| match kind {
+        SequenceKind::Csi => (kind, csi_len(bytes, from)),
+        _ => (kind, string_len(bytes, from, kind == SequenceKind::Osc)),
+    })
+}
+
+/// A control function recognized by [Action::parse].
+#[derive(Clone)]
+pub enum Action {
+    /// CUP, see [crate::cursor::set_position].
+    SetPosition(usize, usize),
+    /// CUU, CUD, CUF, CUB, CNL and CPL, see [crate::cursor::move_cursor].
+    MoveCursor(Direction, usize),
+    /// ED, see [crate::editor::erase_in_page].
+    EraseInPage(AreaPosition),
+    /// EL, see [crate::editor::erase_in_line].
+    EraseInLine(AreaPosition),
+    /// ICH, see [crate::editor::insert_char].
+    InsertChar(usize),
+    /// DCH, see [crate::editor::delete_char].
+    DeleteChar(usize),
+    /// IL, see [crate::editor::insert_line].
+    InsertLine(usize),
+    /// DL, see [crate::editor::delete_line].
+    DeleteLine(usize),
+    /// ECH, see [crate::editor::erase_char].
+    EraseChar(usize),
+    /// SD, SL, SR and SU, see [crate::display::scroll].
+    Scroll(usize, ScrollDirection),
+    /// SGR, see [crate::presentation::select_graphic].
+    SelectGraphic(GraphicSelection),
+    /// Any other control function.
+    Other(AnySequence),
+}
+
+impl Action {
+    /// Recognizes the control function at the start of `input`.
+    ///
+    /// Returns the action and the number of bytes it spans, or `None` if `input` starts with a graphic
+    /// character, a control string or an incomplete control function.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::cursor::set_position;
+    /// use coded_chars::parser::Action;
+    ///
+    /// let (action, len) = Action::parse("\x1b[5;1HHello").unwrap();
+    /// assert!(matches!(action, Action::SetPosition(5, 1)));
+    /// assert_eq!(len, 6);
+    ///
+    /// // The action emits the same sequence
+    /// assert_eq!(action.to_sequence().to_string(), set_position(5, 1).to_string());
+    /// ```
+    pub fn parse(input: &str) -> Option<(Action, usize)> {
+        let (kind, len) = classify(input)?;
+        let action = match kind {
+            SequenceKind::Csi => {
+                let (parameters, end, _) = csi_parts(input)?;
+                csi_action(parameters, end).unwrap_or_else(|| Action::Other(AnySequence::Csi(csi_sequence(parameters, end))))
+            }
+            SequenceKind::Escape if len == 2 => {
+                Action::Other(AnySequence::Esc(EscapeSequence::try_new(input[1..].chars().next()?).ok()?))
+            }
+            SequenceKind::Control => Action::Other(AnySequence::Control(input.chars().next()?)),
+            _ => return None,
+        };
+        Some((action, len))
+    }
+
+    /// Returns the control function emitting this action.
+    pub fn to_sequence(&self) -> AnySequence {
+        AnySequence::Csi(match self {
+            Action::SetPosition(l, c) => set_position(*l, *c),
+            Action::MoveCursor(direction, n) => move_cursor(*direction, *n),
+            Action::EraseInPage(area_position) => erase_in_page(*area_position),
+            Action::EraseInLine(area_position) => erase_in_line(*area_position),
+            Action::InsertChar(n) => insert_char(*n),
+            Action::DeleteChar(n) => delete_char(*n),
+            Action::InsertLine(n) => insert_line(*n),
+            Action::DeleteLine(n) => delete_line(*n),
+            Action::EraseChar(n) => erase_char(*n),
+            Action::Scroll(n, scroll_direction) => scroll(*n, *scroll_direction),
+            Action::SelectGraphic(graphic_selection) => graphic_selection.get(),
+            Action::Other(sequence) => return sequence.clone(),
+        })
+    }
+}
+
+fn csi_sequence(parameters: &str, end: &str) -> ControlSequence {
+    match parameters.strip_prefix('?') {
+        Some(parameters) => ControlSequence::private(&parameters.split(';').collect::<Vec<_>>(), end),
+        None => ControlSequence::new(&parameters.split(';').collect::<Vec<_>>(), end),
+    }
+}
+
+/// An element of a string, yielded by [tokenize].
+#[derive(Clone)]
+pub enum Token<'a> {
+    /// A run of graphic characters, borrowed from the input.
+    ///
+    /// Control strings and incomplete control functions are also yielded as text, untouched.
+    Text(&'a str),
+    /// A control sequence, see [ControlSequence].
+    Csi(ControlSequence),
+    /// An escape sequence, see [EscapeSequence].
+    Esc(EscapeSequence),
+    /// A single C0 or C1 control character.
+    Control(char),
+}
+
+/// Walks `input`, yielding its text runs and its control functions in order.
+///
+/// ### Example
+/// ```
+/// use coded_chars::characters::BEL;
+/// use coded_chars::parser::{tokenize, Token};
+///
+/// let tokens = tokenize("\x1b[1mHello\x07").collect::<Vec<_>>();
+/// assert!(matches!(tokens[..], [Token::Csi(_), Token::Text("Hello"), Token::Control(BEL)]));
+/// ```
+pub fn tokenize(input: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut rest = input;
+    core::iter::from_fn(move || {
+        let (token, len) = match classify(rest) {
+            None if rest.is_empty() => return None,
+            None => {
+                let len = rest.char_indices()
+                    .find(|(i, _)| classify(&rest[*i..]).is_some())
+                    .map_or(rest.len(), |(i, _)| i);
+                (Token::Text(&rest[..len]), len)
+            }
+            Some((kind, len)) => (match kind {
+                SequenceKind::Csi => csi_parts(rest)
+                    .map(|(parameters, end, _)| Token::Csi(csi_sequence(parameters, end))),
+                SequenceKind::Escape if len == 2 => rest[1..].chars().next()
+                    .and_then(|c| EscapeSequence::try_new(c).ok())
+                    .map(Token::Esc),
+                SequenceKind::Control => rest.chars().next().map(Token::Control),
+                _ => None,
+            }.unwrap_or(Token::Text(&rest[..len])), len),
+        };
+        rest = &rest[len..];
+        Some(token)
+    })
+}
+
+fn csi_action(parameters: &str, end: &str) -> Option<Action> {
+    let numbers = parameters.split(';')
+        .map(|n| if n.is_empty() { Ok(None) } else { n.parse::<usize>().map(Some) })
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let n = |i: usize, default: usize| numbers.get(i).copied().flatten().unwrap_or(default);
+    let area_position = || match n(0, 0) {
+        0 => Some(AreaPosition::AfterCursor),
+        1 => Some(AreaPosition::BeforeCursor),
+        2 => Some(AreaPosition::Whole),
+        _ => None,
+    };
+
+    Some(match end {
+        "H" => Action::SetPosition(n(0, 1), n(1, 1)),
+        "A" => Action::MoveCursor(Direction::Up, n(0, 1)),
+        "B" => Action::MoveCursor(Direction::Down, n(0, 1)),
+        "C" => Action::MoveCursor(Direction::Forward, n(0, 1)),
+        "D" => Action::MoveCursor(Direction::Backward, n(0, 1)),
+        "E" => Action::MoveCursor(Direction::NextLine, n(0, 1)),
+        "F" => Action::MoveCursor(Direction::PreviousLine, n(0, 1)),
+        "J" => Action::EraseInPage(area_position()?),
+        "K" => Action::EraseInLine(area_position()?),
+        "@" => Action::InsertChar(n(0, 1)),
+        "P" => Action::DeleteChar(n(0, 1)),
+        "L" => Action::InsertLine(n(0, 1)),
+        "M" => Action::DeleteLine(n(0, 1)),
+        "X" => Action::EraseChar(n(0, 1)),
+        "T" => Action::Scroll(n(0, 1), ScrollDirection::Down),
+        " @" => Action::Scroll(n(0, 1), ScrollDirection::Left),
+        " A" => Action::Scroll(n(0, 1), ScrollDirection::Right),
+        "S" => Action::Scroll(n(0, 1), ScrollDirection::Up),
+        "m" => {
+            let mut graphic_selection = GraphicSelection::new();
+            for code in parameters.split(';') {
+                graphic_selection.add(if code.is_empty() { "0" } else { code });
+            }
+            Action::SelectGraphic(graphic_selection)
+        }
+        _ => return None,
+    })
+}
+
+/// Collects the [Action]s recognized by a [vte] parser, to combine the emitting side of this crate with the
+/// parsing backend of `vte`. Available with the `vte` feature.
+///
+/// The printed characters, the control strings and the escape sequences with intermediate bytes are
+/// ignored. `vte` reports an omitted parameter as 0, which is read as the default value of the parameter.
+///
+/// ### Example
+/// ```
+/// # #[cfg(feature = "vte")]
+/// # {
+/// use coded_chars::parser::{Action, ActionPerformer};
+///
+/// let mut performer = ActionPerformer::new();
+/// vte::Parser::new().advance(&mut performer, b"Hello\x1b[5;1H");
+/// assert!(matches!(performer.actions(), [Action::SetPosition(5, 1)]));
+/// # }
+/// ```
+#[cfg(feature = "vte")]
+#[derive(Clone, Default)]
+pub struct ActionPerformer {
+    actions: Vec<Action>,
+}
+
+#[cfg(feature = "vte")]
+impl ActionPerformer {
+    pub fn new() -> Self { Self::default() }
+
+    /// Returns the actions recognized so far, in order.
+    pub fn actions(&self) -> &[Action] { &self.actions }
+
+    /// Returns the actions recognized so far, leaving none in this performer.
+    pub fn take_actions(&mut self) -> Vec<Action> { core::mem::take(&mut self.actions) }
+}
+
+#[cfg(feature = "vte")]
+impl vte::Perform for ActionPerformer {
+    fn execute(&mut self, byte: u8) {
+        self.actions.push(Action::Other(AnySequence::Control(char::from(byte))));
+    }
+
+    fn csi_dispatch(&mut self, params: &vte::Params, intermediates: &[u8], ignore: bool, action: char) {
+        if ignore {
+            return;
+        }
+        // The private markers (`<` to `?`) are collected with the intermediate bytes.
+        let (private, intermediates): (Vec<u8>, Vec<u8>) = intermediates.iter().partition(|b| (0x3C..=0x3F).contains(*b));
+        let parameters = params.iter()
+            .map(|param| match param {
+                [0] => String::new(),
+                _ => param.iter().map(u16::to_string).collect::<Vec<_>>().join(":"),
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        let parameters = format!("{}{}", String::from_utf8_lossy(&private), parameters);
+        let end = format!("{}{}", String::from_utf8_lossy(&intermediates), action);
+        self.actions.push(csi_action(&parameters, &end).unwrap_or_else(|| Action::Other(AnySequence::Csi(csi_sequence(&parameters, &end)))));
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], ignore: bool, byte: u8) {
+        if ignore || !intermediates.is_empty() {
+            return;
+        }
+        if let Ok(sequence) = EscapeSequence::try_new(char::from(byte)) {
+            self.actions.push(Action::Other(AnySequence::Esc(sequence)));
+        }
+    }
+}
+
+/// An invalid control function found by [validate].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The control function starting at this byte offset is not terminated before the end of the input.
+    Unterminated(usize),
+    /// The control function starting at this byte offset contains bytes it may not contain.
+    Malformed(usize),
+}
+
+impl ValidationError {
+    /// The byte offset where the invalid control function starts.
+    pub fn offset(&self) -> usize {
+        match self {
+            ValidationError::Unterminated(offset) | ValidationError::Malformed(offset) => *offset,
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationError::Unterminated(offset) => write!(f, "unterminated control function at byte {}", offset),
+            ValidationError::Malformed(offset) => write!(f, "malformed control function at byte {}", offset),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
+/// Checks that every control function in `input` is complete and well-formed.
+///
+/// Returns all the invalid control functions found, with their byte offsets.
+///
+/// ### Example
+/// ```
+/// use coded_chars::parser::{validate, ValidationError};
+/// use coded_chars::presentation::{format_str, select_graphic};
+///
+/// assert_eq!(validate(&format_str("Hello", select_graphic().bold())), Ok(()));
+/// assert_eq!(validate("Hello\x1b[1;3"), Err(vec![ValidationError::Unterminated(5)]));
+/// ```
+pub fn validate(input: &str) -> Result<(), Vec<ValidationError>> {
+    let mut errors = vec![];
+    let mut offset = 0;
+
+    while offset < input.len() {
+        let rest = &input[offset..];
+        let Some((kind, len)) = classify(rest) else {
+            offset += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        let sequence = &rest[..len];
+        let last = sequence.as_bytes()[len - 1];
+        let error = match kind {
+            SequenceKind::Control => None,
+            // Both introducers of a CSI are 2 bytes long in UTF-8.
+            SequenceKind::Csi if len <= 2 || !(0x40..=0x7E).contains(&last) => Some(ValidationError::Unterminated(offset)),
+            SequenceKind::Csi => csi_parts(sequence).map_or(Some(ValidationError::Malformed(offset)), |_| None),
+            SequenceKind::Escape if len == rest.len() && !(0x30..=0x7E).contains(&last) => Some(ValidationError::Unterminated(offset)),
+            SequenceKind::Escape if len < 2 || !(0x30..=0x7E).contains(&last) => Some(ValidationError::Malformed(offset)),
+            SequenceKind::Escape => None,
+            _ => {
+                let terminated = sequence.ends_with("\x1b\\")
+                    || sequence.ends_with('\u{9C}')
+                    || (kind == SequenceKind::Osc && sequence.ends_with('\x07'));
+                (!terminated).then_some(ValidationError::Unterminated(offset))
+            }
+        };
+        errors.extend(error);
+        offset += len;
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+/// Returns the byte length of the control function at the start of `input`, if any.
+pub(crate) fn sequence_len(input: &str) -> Option<usize> {
+    classify(input).map(|(_, len)| len)
+}
+
+/// Splits the control sequence at the start of `input` into its parameters and its end (intermediate
+/// bytes followed by the final byte).
+///
+/// Returns `None` if `input` does not start with a complete control sequence.
+pub(crate) fn csi_parts(input: &str) -> Option<(&str, &str, usize)> {
+    let (kind, len) = classify(input)?;
+    if kind != SequenceKind::Csi {
+        return None;
+    }
+    let from = if input.starts_with(ESC) { 2 } else { '\u{9B}'.len_utf8() };
+    let sequence = &input[from..len];
+    let (parameters, end) = csi_split(sequence.as_bytes()).ok()?;
+    Some((&sequence[..parameters.len()], &sequence[parameters.len()..parameters.len() + end.len()], len))
+}
+
+/// Splits the body of a CSI, following its introducer, into its parameter bytes and its end, made of the
+/// intermediate bytes followed by the final byte.
+///
+/// Fails with the offset of the first byte out of place, or with `None` when the final byte is missing.
+pub(crate) fn csi_split(body: &[u8]) -> Result<(&[u8], &[u8]), Option<usize>> {
+    let parameters_len = body.iter().take_while(|b| (0x30..=0x3F).contains(*b)).count();
+    let intermediates_len = body[parameters_len..].iter().take_while(|b| (0x20..=0x2F).contains(*b)).count();
+    let final_at = parameters_len + intermediates_len;
+    match body.get(final_at) {
+        None => Err(None),
+        Some(0x40..=0x7E) => Ok((&body[..parameters_len], &body[parameters_len..=final_at])),
+        Some(_) => Err(Some(final_at)),
+    }
+}
+
+fn escape_len(bytes: &[u8]) -> usize {
+    bytes[1..].iter()
+        .position(|b| !(0x20..=0x2F).contains(b))
+        .map_or(bytes.len(), |i| match bytes[1 + i] {
+            0x30..=0x7E => i + 2,
+            _ => i + 1,
+        })
+}
+
+fn csi_len(bytes: &[u8], from: usize) -> usize {
+    bytes[from..].iter()
+        .position(|b| (0x40..=0x7E).contains(b))
+        .map_or(bytes.len(), |i| from + i + 1)
+}
+
+fn string_len(bytes: &[u8], from: usize, bel_terminates: bool) -> usize {
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            0x07 if bel_terminates => return i + 1,
+            b if b == ESC as u8 && bytes.get(i + 1) == Some(&b'\\') => return i + 2,
+            // ST as a single C1 character, encoded in UTF-8
+            0xC2 if bytes.get(i + 1) == Some(&0x9C) => return i + 2,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::characters::BEL;
+    use crate::cursor::set_position;
+    use crate::format::NEL;
+    use crate::parser::{classify, tokenize, validate, Action, SequenceKind, Token, ValidationError};
+    use crate::presentation::{format_str, select_graphic};
+
+    #[test]
+    fn classify_kinds() {
+        assert_eq!(classify(&format!("{}text", set_position(10, 2))), Some((SequenceKind::Csi, 7)));
+        assert_eq!(classify("\x1b[ 0Htext"), Some((SequenceKind::Csi, 5)));
+        assert_eq!(classify("\u{9B}2Jtext"), Some((SequenceKind::Csi, 4)));
+        assert_eq!(classify("\x1b]0;title\x07text"), Some((SequenceKind::Osc, 10)));
+        assert_eq!(classify("\x1b]0;title\x1b\\text"), Some((SequenceKind::Osc, 11)));
+        assert_eq!(classify("\x1bP1$r0m\x1b\\text"), Some((SequenceKind::Dcs, 9)));
+        assert_eq!(classify("\x1b_cmd\x1b\\"), Some((SequenceKind::Apc, 7)));
+        assert_eq!(classify("\x1b^msg\x1b\\"), Some((SequenceKind::Pm, 7)));
+        assert_eq!(classify("\x1bXstr\x1b\\"), Some((SequenceKind::Sos, 7)));
+        assert_eq!(classify(&format!("{}text", NEL)), Some((SequenceKind::Escape, 2)));
+        assert_eq!(classify("\x1b(Btext"), Some((SequenceKind::Escape, 3)));
+        assert_eq!(classify(&format!("{}text", BEL)), Some((SequenceKind::Control, 1)));
+        assert_eq!(classify("text"), None);
+        assert_eq!(classify(""), None);
+    }
+
+    #[test]
+    fn classify_unterminated() {
+        assert_eq!(classify("\x1b[12;4"), Some((SequenceKind::Csi, 6)));
+        assert_eq!(classify("\x1b]0;title"), Some((SequenceKind::Osc, 9)));
+        assert_eq!(classify("\x1b"), Some((SequenceKind::Escape, 1)));
+    }
+
+    #[test]
+    fn validate_stream() {
+        let valid = format!("{}{}\x1b]0;title\x07{}", set_position(1, 1), format_str("Hello", select_graphic().bold()), NEL);
+        assert_eq!(validate(&valid), Ok(()));
+        assert_eq!(validate("art\x1b[1;31"), Err(vec![ValidationError::Unterminated(3)]));
+        assert_eq!(validate("\x1b[1\n2Hart\x1b]0;title"), Err(vec![
+            ValidationError::Malformed(0),
+            ValidationError::Unterminated(9),
+        ]));
+        assert_eq!(validate("art\x1b\x07"), Err(vec![ValidationError::Malformed(3)]));
+        assert_eq!(validate("art\x1b"), Err(vec![ValidationError::Unterminated(3)]));
+        assert_eq!(validate("art\x1b["), Err(vec![ValidationError::Unterminated(3)]));
+    }
+
+    #[test]
+    fn action_round_trip() {
+        for input in ["\x1b[12;40H", "\x1b[3C", "\x1b[2J", "\x1b[1K", "\x1b[4P", "\x1b[3L", "\x1b[2 @", "\x1b[1;31m", "\x1bM", "\x07", "\x1b[?25l"] {
+            let (action, len) = Action::parse(input).unwrap();
+            assert_eq!(len, input.len());
+            assert_eq!(action.to_sequence().to_string(), input);
+        }
+        assert!(matches!(Action::parse("\x1b[H"), Some((Action::SetPosition(1, 1), 3))));
+        assert!(matches!(Action::parse("\x1b[K"), Some((Action::EraseInLine(_), 3))));
+        assert!(Action::parse("text").is_none());
+        assert!(Action::parse("\x1b]0;title\x07").is_none());
+    }
+
+    #[cfg(feature = "vte")]
+    #[test]
+    fn vte_actions() {
+        use crate::parser::ActionPerformer;
+
+        let mut performer = ActionPerformer::new();
+        let mut parser = vte::Parser::new();
+        parser.advance(&mut performer, b"ab\x1b[12;40Hc\x1b[;5H\x1b[?25l\x1bM\x07\x1b[1;3");
+        let actions = performer.take_actions();
+        assert_eq!(actions.len(), 5);
+        assert!(matches!(actions[0], Action::SetPosition(12, 40)));
+        assert!(matches!(actions[1], Action::SetPosition(1, 5)));
+        assert_eq!(actions[2].to_sequence().to_string(), "\x1b[?25l");
+        assert_eq!(actions[3].to_sequence().to_string(), "\x1bM");
+        assert_eq!(actions[4].to_sequence().to_string(), "\x07");
+
+        parser.advance(&mut performer, b"m");
+        assert!(matches!(performer.actions(), [Action::SelectGraphic(_)]));
+        assert_eq!(performer.actions()[0].to_sequence().to_string(), "\x1b[1;3m");
+    }
+
+    #[test]
+    fn tokenize_stream() {
+        let input = format!("{}{}{}\x1b]0;title\x07", format_str("Hello", select_graphic().bold()), set_position(2, 1), BEL);
+        let tokens = tokenize(&input).collect::<Vec<_>>();
+        assert_eq!(tokens.len(), 6);
+        assert!(matches!(&tokens[0], Token::Csi(sequence) if sequence.to_string() == "\x1b[1m"));
+        assert!(matches!(tokens[1], Token::Text("Hello")));
+        assert!(matches!(&tokens[2], Token::Csi(sequence) if sequence.to_string() == "\x1b[0m"));
+        assert!(matches!(&tokens[3], Token::Csi(sequence) if sequence.to_string() == "\x1b[2;1H"));
+        assert!(matches!(tokens[4], Token::Control(BEL)));
+        assert!(matches!(tokens[5], Token::Text("\x1b]0;title\x07")));
+        assert!(matches!(tokenize(&NEL.to_string()).next(), Some(Token::Esc(_))));
+        assert!(tokenize("").next().is_none());
+    }
+}
@@ -1,8 +1,12 @@
 //! This module provides control function to change the presentation.
 
-use std::fmt::{Display, Formatter};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::{Debug, Display, Formatter};
 use crate::control::ControlSequence;
 use crate::escape::{escape, EscapeSequence};
+use crate::sgr::SgrState;
 
 /// # Break permitted here
 ///
@@ -33,8 +37,24 @@ pub fn dimension_text(l: usize, c: usize) -> ControlSequence {
 ///
 /// FNT is used to identify the character font to be selected as primary or alternative font by subsequent
 /// occurrences of SELECT GRAPHIC RENDITION (SGR) in the data stream.
+///
+/// The second parameter, selecting the character repertoire, is 0 : see [select_font_with_charset].
 pub fn select_font(font: Font) -> ControlSequence {
-    ControlSequence::new(&[&font.to_string(), "0"], " D")
+    select_font_with_charset(font, 0)
+}
+
+/// # FNT - Font selection
+///
+/// Same as [select_font], with `charset` as the second parameter.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::{select_font_with_charset, Font};
+///
+/// assert_eq!(select_font_with_charset(Font::Alternative1, 2).to_string(), "\x1b[1;2 D");
+/// ```
+pub fn select_font_with_charset(font: Font, charset: usize) -> ControlSequence {
+    ControlSequence::new(&[&font.to_string(), &charset.to_string()], " D")
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -51,8 +71,35 @@ pub enum Font {
     Alternative9,
 }
 
+impl Font {
+    /// Returns the SGR parameter value selecting this font, from `10` for [Font::Primary] to `19` for
+    /// [Font::Alternative9].
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::presentation::{select_graphic, Font};
+    ///
+    /// assert_eq!(Font::Alternative3.to_sgr(), "13");
+    /// assert_eq!(select_graphic().font(Font::Alternative3).to_string(), "\x1b[13m");
+    /// ```
+    pub fn to_sgr(&self) -> &'static str {
+        match self {
+            Font::Primary => "10",
+            Font::Alternative1 => "11",
+            Font::Alternative2 => "12",
+            Font::Alternative3 => "13",
+            Font::Alternative4 => "14",
+            Font::Alternative5 => "15",
+            Font::Alternative6 => "16",
+            Font::Alternative7 => "17",
+            Font::Alternative8 => "18",
+            Font::Alternative9 => "19",
+        }
+    }
+}
+
 impl Display for Font {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Font::Primary => "0",
             Font::Alternative1 => "1",
@@ -93,7 +140,7 @@ pub enum Combination {
 }
 
 impl Display for Combination {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Self::Two => "0",
             Self::Start => "1",
@@ -164,7 +211,7 @@ pub enum JustifyMode {
 }
 
 impl Display for JustifyMode {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             JustifyMode::None => "0",
             JustifyMode::WordFill => "1",
@@ -199,7 +246,7 @@ pub enum Expansion {
 }
 
 impl Display for Expansion {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Self::Normal => "0",
             Self::Expanded => "1",
@@ -242,7 +289,7 @@ pub enum PageFormat {
 }
 
 impl Display for PageFormat {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             PageFormat::TallText => "0",
             PageFormat::WideText => "1",
@@ -318,7 +365,7 @@ pub enum TextDelimiter {
 }
 
 impl Display for TextDelimiter {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             TextDelimiter::End => "0",
             TextDelimiter::BeginPrincipal => "1",
@@ -368,7 +415,7 @@ pub enum Layout {
 }
 
 impl Display for Layout {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Layout::FlushHome => "0",
             Layout::FlushHomeAndFill => "1",
@@ -392,6 +439,47 @@ pub fn repeat(n: usize) -> ControlSequence {
     ControlSequence::new(&[&n.to_string()], "b")
 }
 
+/// Returns `c` printed `n` times, using REP after the first occurrence so the repeated item is always a graphic
+/// character.
+///
+/// A control character cannot be repeated by REP, it is then printed `n` times.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::repeat_char;
+///
+/// assert_eq!(repeat_char('=', 5), "=\x1b[4b");
+/// assert_eq!(repeat_char('=', 1), "=");
+/// assert_eq!(repeat_char('=', 0), "");
+/// ```
+pub fn repeat_char(c: char, n: usize) -> String {
+    match n {
+        0 => String::new(),
+        1 => c.to_string(),
+        _ if c.is_control() => c.to_string().repeat(n),
+        _ => format!("{}{}", c, repeat(n - 1)),
+    }
+}
+
+/// Returns the stream printing `n` times the character `c` with `style`, see [repeat_char].
+///
+/// The style is applied once, before `c`, so that REP follows the graphic character it repeats and not the
+/// SGR sequence, the effect of REP being undefined after a control function. The style is reset afterward.
+/// Nothing is printed if `n` is 0.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::{repeat_styled, select_graphic};
+///
+/// assert_eq!(repeat_styled('*', 5, select_graphic().fg_red()), "\x1b[31m*\x1b[4b\x1b[0m");
+/// ```
+pub fn repeat_styled(c: char, n: usize, style: &GraphicSelection) -> String {
+    match n {
+        0 => String::new(),
+        _ => format_str(&repeat_char(c, n), style),
+    }
+}
+
 /// # SACS - Set additional character separation
 ///
 /// SACS is used to establish extra inter-character escapement for subsequent text. The established extra
@@ -414,7 +502,7 @@ pub fn select_alternative() -> PresentationVariant {
     PresentationVariant::new()
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct PresentationVariant {
     modes: Vec<String>,
 }
@@ -507,6 +595,11 @@ impl PresentationVariant {
     /// [Self::character_medial], and [Self::character_final] for the next single graphic character only.
     pub fn character_cancel(&mut self) -> &mut Self { self.add("22") }
 
+    /// Returns an iterator over the parameters of this sequence, in order.
+    pub fn params_iter(&self) -> impl Iterator<Item = &str> {
+        self.modes.iter().map(String::as_str)
+    }
+
     pub fn get(&self) -> ControlSequence {
         ControlSequence::new(&self.modes.iter().map(|s| s.as_str()).collect::<Vec<_>>(), " ]")
     }
@@ -515,8 +608,17 @@ impl PresentationVariant {
         self
     }
 }
+impl<'a> IntoIterator for &'a PresentationVariant {
+    type Item = &'a str;
+    type IntoIter = core::iter::Map<core::slice::Iter<'a, String>, fn(&'a String) -> &'a str>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.modes.iter().map(String::as_str as fn(&'a String) -> &'a str)
+    }
+}
+
 impl Display for PresentationVariant {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.get())
     }
 }
@@ -552,7 +654,7 @@ pub enum Orientation {
 }
 
 impl Display for Orientation {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Orientation::North => "0",
             Orientation::NorthWest => "1",
@@ -588,7 +690,7 @@ pub enum CharacterPath {
 }
 
 impl Display for CharacterPath {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             CharacterPath::LeftToRight => "1",
             CharacterPath::RightToLeft => "2",
@@ -619,7 +721,7 @@ pub enum PathEffect {
 }
 
 impl Display for PathEffect {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             PathEffect::Undefined => "0",
             PathEffect::UpdatePresentation => "1",
@@ -667,7 +769,7 @@ pub enum StringDirection {
 }
 
 impl Display for StringDirection {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             StringDirection::End => "0",
             StringDirection::StartLeftToRight => "1",
@@ -691,7 +793,7 @@ pub enum MovementDirection {
 }
 
 impl Display for MovementDirection {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             MovementDirection::Same => "0",
             MovementDirection::Opposite => "1",
@@ -699,6 +801,125 @@ impl Display for MovementDirection {
     }
 }
 
+/// A color of the foreground or the background, see [GraphicSelection::fg] and [GraphicSelection::bg].
+///
+/// The parameters emitted for a foreground (resp. background) color are :
+/// - `30`–`37` (resp. `40`–`47`) for the standard colors, from [Color::Black] to [Color::Gray],
+/// - `90`–`97` (resp. `100`–`107`) for the bright colors,
+/// - `38;5;n` (resp. `48;5;n`) for an indexed color,
+/// - `38;2;r;g;b` (resp. `48;2;r;g;b`) for a direct color,
+/// - `39` (resp. `49`) for the default color.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::{select_graphic, Color};
+///
+/// let warning = true;
+/// let color = if warning { Color::Rgb(255, 128, 0) } else { Color::Green };
+/// assert_eq!(select_graphic().fg(color).to_string(), "\x1b[38;2;255;128;0m");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+    /// A bright color, from `0` (bright black) to `7` (bright gray) in the order of the standard colors.
+    /// Higher values wrap around, taken modulo 8: `Bright(9)` is emitted as `Bright(1)` (bright red).
+    Bright(u8),
+    /// A color of the 256 colors palette.
+    Indexed(u8),
+    /// A direct color (ISO 8613-6).
+    Rgb(u8, u8, u8),
+    /// The default color (implementation-defined).
+    Default,
+}
+
+impl Color {
+    const STANDARD: [Color; 8] = [
+        Color::Black, Color::Red, Color::Green, Color::Yellow,
+        Color::Blue, Color::Magenta, Color::Cyan, Color::Gray,
+    ];
+
+    /// Returns one of the 16 standard colors: `0`–`7` are the standard colors, `8`–`15` the bright colors.
+    /// Any higher index is an indexed color.
+    pub fn from_index16(idx: u8) -> Self {
+        match idx {
+            0..=7 => Self::STANDARD[idx as usize],
+            8..=15 => Self::Bright(idx - 8),
+            _ => Self::Indexed(idx),
+        }
+    }
+
+    /// Returns the index of this color in the xterm 256 colors palette, a direct color being approximated by
+    /// [rgb_to_256].
+    ///
+    /// Returns `None` for the default color, which is implementation-defined.
+    pub fn to_256(&self) -> Option<u8> {
+        match self {
+            Color::Bright(n) => Some(8 + n % 8),
+            Color::Indexed(n) => Some(*n),
+            Color::Rgb(r, g, b) => Some(rgb_to_256(*r, *g, *b)),
+            Color::Default => None,
+            standard => Self::STANDARD.iter().position(|c| c == standard).map(|i| i as u8),
+        }
+    }
+
+    /// Returns the SGR parameters of this color, `base` being `30` for the foreground or `40` for the
+    /// background.
+    fn parameters(&self, base: u16) -> String {
+        match self {
+            Color::Bright(n) => (base + 60 + (*n as u16 % 8)).to_string(),
+            Color::Indexed(n) => format!("{};5;{}", base + 8, n),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+            Color::Default => (base + 9).to_string(),
+            standard => (base + Self::STANDARD.iter().position(|c| c == standard).unwrap_or(0) as u16).to_string(),
+        }
+    }
+}
+
+/// Returns the index of the xterm 256 colors palette nearest to the direct color `(r, g, b)`.
+///
+/// The nearest color of the 6×6×6 color cube (`16`–`231`) is compared with the nearest gray of the grayscale
+/// ramp (`232`–`255`), the 16 standard colors being left out as their values depend on the terminal.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::{rgb_to_256, select_graphic};
+///
+/// // Degrades a direct color for a terminal supporting 256 colors only
+/// let orange = rgb_to_256(255, 135, 0);
+/// assert_eq!(select_graphic().fg_color_256(orange).to_string(), "\x1b[38;5;208m");
+/// ```
+pub fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)].iter()
+            .map(|(a, b)| (*a as i32 - *b as i32).pow(2))
+            .sum::<i32>()
+    };
+    let level = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        v => (v - 35) / 40,
+    };
+
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (LEVELS[ri as usize], LEVELS[gi as usize], LEVELS[bi as usize]);
+    let gray_index = ((r as u16 + g as u16 + b as u16) / 3).saturating_sub(3).min(238) as u8 / 10;
+    let gray = 8 + gray_index * 10;
+
+    if distance((gray, gray, gray)) < distance(cube) {
+        232 + gray_index
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
 /// # SGR - Select graphic rendition
 ///
 /// SGR is used to establish one or more graphic rendition aspects for subsequent text. The established
@@ -710,22 +931,65 @@ impl Display for MovementDirection {
 ///
 /// // Direct format
 /// use coded_chars::presentation::select_graphic;
-/// println!("Hello {}{}{} !", select_graphic().fg_red().bold().underline(), "World", select_graphic().default());
+/// println!("Hello {}{}{} !", select_graphic().fg_red().bold().underline(), "World", select_graphic().default_rendition());
 /// ```
 pub fn select_graphic() -> GraphicSelection {
     GraphicSelection::new()
 }
 
-#[derive(Clone)]
+/// # SGR - Select graphic rendition
+///
+/// Builds an SGR sequence from arbitrary `codes`, for the aspects not covered by [GraphicSelection].
+///
+/// The codes are printed as is and in order : the caller is responsible for grouping the sub-parameters of
+/// an extended color with their code, like `"38", "5", "200"`.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::sgr;
+///
+/// // Curly underline, an extension of some terminals
+/// assert_eq!(sgr(&["4:3"]).to_string(), "\x1b[4:3m");
+/// ```
+pub fn sgr(codes: &[&str]) -> ControlSequence {
+    ControlSequence::new(codes, "m")
+}
+
+/// The parameters of an SGR sequence.
+///
+/// [Default] creates an empty selection, like [GraphicSelection::new]. The default rendition (`0`) is added
+/// with [GraphicSelection::default_rendition].
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct GraphicSelection {
     modes: Vec<String>,
 }
+
+impl Default for GraphicSelection {
+    fn default() -> Self { Self::new() }
+}
+
+impl From<&[&str]> for GraphicSelection {
+    fn from(codes: &[&str]) -> Self { Self::from_codes(codes) }
+}
+
 impl GraphicSelection {
     pub fn new() -> Self { Self { modes: vec![] } }
 
+    /// Creates a selection from stored parameter values, like `["1", "31"]`.
+    ///
+    /// ### Example
+    /// ```
+    /// use coded_chars::presentation::GraphicSelection;
+    ///
+    /// assert_eq!(GraphicSelection::from_codes(&["1", "31"]).get().to_string(), "\x1b[1;31m");
+    /// ```
+    pub fn from_codes(codes: &[&str]) -> Self {
+        Self { modes: codes.iter().map(|code| code.to_string()).collect() }
+    }
+
     /// Default rendition (implementation-defined), cancels the effect of any preceding occurrence of SGR in
     /// the data stream regardless of the setting of the GRAPHIC RENDITION COMBINATION MODE (GRCM).
-    pub fn default(&mut self) -> &mut Self { self.add("0") }
+    pub fn default_rendition(&mut self) -> &mut Self { self.add("0") }
 
     /// Bold or increased intensity
     pub fn bold(&mut self) -> &mut Self { self.add("1") }
@@ -755,6 +1019,10 @@ impl GraphicSelection {
     pub fn alter7_font(&mut self) -> &mut Self { self.add("17") }
     pub fn alter8_font(&mut self) -> &mut Self { self.add("18") }
     pub fn alter9_font(&mut self) -> &mut Self { self.add("19") }
+
+    /// Selects `font` (`10` to `19`), the primary or alternative fonts being identified by FONT SELECTION
+    /// (FNT), see [select_font].
+    pub fn font(&mut self, font: Font) -> &mut Self { self.add(font.to_sgr()) }
     pub fn gothic_font(&mut self) -> &mut Self { self.add("20") }
     pub fn double_underline(&mut self) -> &mut Self { self.add("21") }
 
@@ -776,55 +1044,294 @@ impl GraphicSelection {
     /// Revealed characters
     pub fn not_conceal(&mut self) -> &mut Self { self.add("28") }
     pub fn not_cross(&mut self) -> &mut Self { self.add("29") }
-    pub fn fg_black(&mut self) -> &mut Self { self.add("30") }
-    pub fn fg_red(&mut self) -> &mut Self { self.add("31") }
-    pub fn fg_green(&mut self) -> &mut Self { self.add("32") }
-    pub fn fg_yellow(&mut self) -> &mut Self { self.add("33") }
-    pub fn fg_blue(&mut self) -> &mut Self { self.add("34") }
-    pub fn fg_magenta(&mut self) -> &mut Self { self.add("35") }
-    pub fn fg_cyan(&mut self) -> &mut Self { self.add("36") }
-    pub fn fg_gray(&mut self) -> &mut Self { self.add("37") }
+
+    /// Normal intensity, neither bold nor faint, same as [Self::not_bold_or_faint]
+    pub fn reset_intensity(&mut self) -> &mut Self { self.not_bold_or_faint() }
+
+    /// Not italicized, same as [Self::not_italic]
+    pub fn reset_italic(&mut self) -> &mut Self { self.not_italic() }
+
+    /// Not underlined, same as [Self::not_underline]
+    pub fn reset_underline(&mut self) -> &mut Self { self.not_underline() }
+
+    /// Steady, same as [Self::not_blink]
+    pub fn reset_blink(&mut self) -> &mut Self { self.not_blink() }
+
+    /// Positive image, same as [Self::not_negative]
+    pub fn reset_inverse(&mut self) -> &mut Self { self.not_negative() }
+
+    /// Revealed characters, same as [Self::not_conceal]
+    pub fn reset_conceal(&mut self) -> &mut Self { self.not_conceal() }
+
+    /// Not crossed-out, same as [Self::not_cross]
+    pub fn reset_strike(&mut self) -> &mut Self { self.not_cross() }
+
+    /// Default foreground color, same as [Self::fg_default]
+    pub fn reset_fg(&mut self) -> &mut Self { self.fg_default() }
+
+    /// Default background color, same as [Self::bg_default]
+    pub fn reset_bg(&mut self) -> &mut Self { self.bg_default() }
+
+    /// Sets the foreground color, see [Color] for the emitted parameters.
+    pub fn fg(&mut self, color: Color) -> &mut Self { self.add(&color.parameters(30)) }
+    pub fn fg_black(&mut self) -> &mut Self { self.fg(Color::Black) }
+    pub fn fg_red(&mut self) -> &mut Self { self.fg(Color::Red) }
+    pub fn fg_green(&mut self) -> &mut Self { self.fg(Color::Green) }
+    pub fn fg_yellow(&mut self) -> &mut Self { self.fg(Color::Yellow) }
+    pub fn fg_blue(&mut self) -> &mut Self { self.fg(Color::Blue) }
+    pub fn fg_magenta(&mut self) -> &mut Self { self.fg(Color::Magenta) }
+    pub fn fg_cyan(&mut self) -> &mut Self { self.fg(Color::Cyan) }
+    pub fn fg_gray(&mut self) -> &mut Self { self.fg(Color::Gray) }
+
+    /// Incomplete : the parameter `38` must be followed by a color selector, see [Self::fg_color_256] and
+    /// [Self::fg_rgb].
+    #[deprecated(note = "emits `38` without a color, use `fg_color_256` or `fg_rgb`")]
     pub fn fg_color(&mut self) -> &mut Self { self.add("38") }
-    pub fn fg_default(&mut self) -> &mut Self { self.add("39") }
-    pub fn bg_black(&mut self) -> &mut Self { self.add("40") }
-    pub fn bg_red(&mut self) -> &mut Self { self.add("41") }
-    pub fn bg_green(&mut self) -> &mut Self { self.add("42") }
-    pub fn bg_yellow(&mut self) -> &mut Self { self.add("43") }
-    pub fn bg_blue(&mut self) -> &mut Self { self.add("44") }
-    pub fn bg_magenta(&mut self) -> &mut Self { self.add("45") }
-    pub fn bg_cyan(&mut self) -> &mut Self { self.add("46") }
-    pub fn bg_gray(&mut self) -> &mut Self { self.add("47") }
+
+    /// Indexed color foreground from the 256 colors palette, emitted as `38;5;index`
+    pub fn fg_color_256(&mut self, index: u8) -> &mut Self { self.fg(Color::Indexed(index)) }
+
+    /// Direct colour foreground (ISO 8613-6), emitted as `38;2;r;g;b`
+    pub fn fg_rgb(&mut self, r: u8, g: u8, b: u8) -> &mut Self { self.fg(Color::Rgb(r, g, b)) }
+    pub fn fg_default(&mut self) -> &mut Self { self.fg(Color::Default) }
+
+    /// Foreground color from the 16 standard colors: `0`–`7` map to `30`–`37`, `8`–`15` to the bright
+    /// colors `90`–`97`. Any higher index is not one of the 16 colors and is emitted as a color of the 256
+    /// colors palette instead, `16` giving `38;5;16`.
+    pub fn fg_index16(&mut self, idx: u8) -> &mut Self { self.fg(Color::from_index16(idx)) }
+    pub fn fg_bright_black(&mut self) -> &mut Self { self.fg(Color::Bright(0)) }
+    pub fn fg_bright_red(&mut self) -> &mut Self { self.fg(Color::Bright(1)) }
+    pub fn fg_bright_green(&mut self) -> &mut Self { self.fg(Color::Bright(2)) }
+    pub fn fg_bright_yellow(&mut self) -> &mut Self { self.fg(Color::Bright(3)) }
+    pub fn fg_bright_blue(&mut self) -> &mut Self { self.fg(Color::Bright(4)) }
+    pub fn fg_bright_magenta(&mut self) -> &mut Self { self.fg(Color::Bright(5)) }
+    pub fn fg_bright_cyan(&mut self) -> &mut Self { self.fg(Color::Bright(6)) }
+    pub fn fg_bright_gray(&mut self) -> &mut Self { self.fg(Color::Bright(7)) }
+
+    /// Sets the background color, see [Color] for the emitted parameters.
+    pub fn bg(&mut self, color: Color) -> &mut Self { self.add(&color.parameters(40)) }
+    pub fn bg_black(&mut self) -> &mut Self { self.bg(Color::Black) }
+    pub fn bg_red(&mut self) -> &mut Self { self.bg(Color::Red) }
+    pub fn bg_green(&mut self) -> &mut Self { self.bg(Color::Green) }
+    pub fn bg_yellow(&mut self) -> &mut Self { self.bg(Color::Yellow) }
+    pub fn bg_blue(&mut self) -> &mut Self { self.bg(Color::Blue) }
+    pub fn bg_magenta(&mut self) -> &mut Self { self.bg(Color::Magenta) }
+    pub fn bg_cyan(&mut self) -> &mut Self { self.bg(Color::Cyan) }
+    pub fn bg_gray(&mut self) -> &mut Self { self.bg(Color::Gray) }
+
+    /// Incomplete : the parameter `48` must be followed by a color selector, see [Self::bg_color_256] and
+    /// [Self::bg_rgb].
+    #[deprecated(note = "emits `48` without a color, use `bg_color_256` or `bg_rgb`")]
     pub fn bg_color(&mut self) -> &mut Self { self.add("48") }
-    pub fn bg_default(&mut self) -> &mut Self { self.add("49") }
+
+    /// Indexed color background from the 256 colors palette, emitted as `48;5;index`
+    pub fn bg_color_256(&mut self, index: u8) -> &mut Self { self.bg(Color::Indexed(index)) }
+
+    /// Direct colour background (ISO 8613-6), emitted as `48;2;r;g;b`
+    pub fn bg_rgb(&mut self, r: u8, g: u8, b: u8) -> &mut Self { self.bg(Color::Rgb(r, g, b)) }
+    pub fn bg_default(&mut self) -> &mut Self { self.bg(Color::Default) }
+
+    /// Background color from the 16 standard colors: `0`–`7` map to `40`–`47`, `8`–`15` to the bright
+    /// colors `100`–`107`. Any higher index is not one of the 16 colors and is emitted as a color of the 256
+    /// colors palette instead, `16` giving `48;5;16`.
+    pub fn bg_index16(&mut self, idx: u8) -> &mut Self { self.bg(Color::from_index16(idx)) }
+    pub fn bg_bright_black(&mut self) -> &mut Self { self.bg(Color::Bright(0)) }
+    pub fn bg_bright_red(&mut self) -> &mut Self { self.bg(Color::Bright(1)) }
+    pub fn bg_bright_green(&mut self) -> &mut Self { self.bg(Color::Bright(2)) }
+    pub fn bg_bright_yellow(&mut self) -> &mut Self { self.bg(Color::Bright(3)) }
+    pub fn bg_bright_blue(&mut self) -> &mut Self { self.bg(Color::Bright(4)) }
+    pub fn bg_bright_magenta(&mut self) -> &mut Self { self.bg(Color::Bright(5)) }
+    pub fn bg_bright_cyan(&mut self) -> &mut Self { self.bg(Color::Bright(6)) }
+    pub fn bg_bright_gray(&mut self) -> &mut Self { self.bg(Color::Bright(7)) }
     pub fn frame(&mut self) -> &mut Self { self.add("51") }
     pub fn encircle(&mut self) -> &mut Self { self.add("52") }
     pub fn overline(&mut self) -> &mut Self { self.add("53") }
     pub fn not_frame_not_encircle(&mut self) -> &mut Self { self.add("54") }
     pub fn not_overline(&mut self) -> &mut Self { self.add("55") }
+
+    /// Indexed underline color from the 256 colors palette, emitted as `58;5;index`
+    pub fn underline_color_256(&mut self, index: u8) -> &mut Self { self.add(&format!("58;5;{}", index)) }
+
+    /// Direct underline color, emitted as `58;2;r;g;b`
+    pub fn underline_color_rgb(&mut self, r: u8, g: u8, b: u8) -> &mut Self { self.add(&format!("58;2;{};{};{}", r, g, b)) }
+
+    /// Default underline color, the color of the foreground
+    pub fn default_underline_color(&mut self) -> &mut Self { self.add("59") }
     pub fn ideogram_underline(&mut self) -> &mut Self { self.add("60") }
     pub fn ideogram_double_underline(&mut self) -> &mut Self { self.add("61") }
     pub fn ideogram_overline(&mut self) -> &mut Self { self.add("62") }
     pub fn ideogram_double_overline(&mut self) -> &mut Self { self.add("63") }
     pub fn ideogram_stress_marking(&mut self) -> &mut Self { self.add("64") }
     pub fn ideogram_cancel(&mut self) -> &mut Self { self.add("65") }
+
+    /// Returns the parameters of this selection, in order.
+    ///
+    /// An extended color is a single parameter (`"38;5;208"`), see [Self::codes] for numeric codes.
+    pub fn modes(&self) -> &[String] { &self.modes }
+
+    /// Returns the numeric SGR codes this selection emits, in order.
+    ///
+    /// Extended color forms are expanded into their sub-parameters, so `fg_rgb(255, 128, 0)` yields
+    /// `[38, 2, 255, 128, 0]`.
+    pub fn codes(&self) -> Vec<u16> {
+        self.modes.iter()
+            .flat_map(|mode| mode.split(';'))
+            .filter_map(|code| code.parse().ok())
+            .collect()
+    }
+
+    /// Returns an iterator over the parameters of this selection, in order.
+    ///
+    /// An extended color is a single parameter (`"38;5;208"`), see [Self::codes] for numeric codes.
+    pub fn params_iter(&self) -> impl Iterator<Item = &str> {
+        self.modes.iter().map(String::as_str)
+    }
+
+    /// Returns whether this selection establishes a foreground color (`30` to `37`, `90` to `97` or `38;…`).
+    ///
+    /// A color cancelled by a later default color (`39`) or reset (`0`) is not established.
+    pub fn has_foreground(&self) -> bool { SgrState::from_codes(&self.codes()).has_foreground() }
+
+    /// Returns whether this selection establishes a background color (`40` to `47`, `100` to `107` or `48;…`).
+    ///
+    /// A color cancelled by a later default color (`49`) or reset (`0`) is not established.
+    pub fn has_background(&self) -> bool { SgrState::from_codes(&self.codes()).has_background() }
+
     pub fn get(&self) -> ControlSequence {
         ControlSequence::new(&self.modes.iter().map(|s| s.as_str()).collect::<Vec<_>>(), "m")
     }
-    fn add(&mut self, s: &str) -> &mut Self {
+
+    /// Returns the printed SGR sequence, same as `to_string` but naming the intent.
+    pub fn to_sequence_string(&self) -> String { self.to_string() }
+
+    /// Returns the SGR sequence as is, for a device where the GRAPHIC RENDITION COMBINATION MODE (GRCM)
+    /// is set to CUMULATIVE : only the aspects specified by this selection are changed, all other aspects
+    /// remain unchanged.
+    ///
+    /// This is the same sequence as [Self::get].
+    pub fn assuming_cumulative(&self) -> ControlSequence { self.get() }
+
+    /// Returns the SGR sequence prefixed by a default rendition (`0`), for a device where the GRAPHIC
+    /// RENDITION COMBINATION MODE (GRCM) is set to REPLACING : each SGR cancels the effect of any
+    /// preceding one, so the selection must be complete.
+    ///
+    /// The resulting sequence establishes exactly the aspects of this selection whatever the setting of GRCM.
+    pub fn assuming_replacing(&self) -> ControlSequence {
+        match self.modes.first().map(String::as_str) {
+            Some("0") => self.get(),
+            _ => {
+                let modes = core::iter::once("0").chain(self.modes.iter().map(|s| s.as_str())).collect::<Vec<_>>();
+                ControlSequence::new(&modes, "m")
+            }
+        }
+    }
+    pub(crate) fn add(&mut self, s: &str) -> &mut Self {
         self.modes.push(s.to_string());
         self
     }
 }
 
+impl<'a> IntoIterator for &'a GraphicSelection {
+    type Item = &'a str;
+    type IntoIter = core::iter::Map<core::slice::Iter<'a, String>, fn(&'a String) -> &'a str>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.modes.iter().map(String::as_str as fn(&'a String) -> &'a str)
+    }
+}
+
 impl Display for GraphicSelection {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.get())
     }
 }
 
+/// Lists the attributes of the selection by name, like `[Bold, FgRed]`.
+///
+/// The extended colors are shown with their values, like `Fg256(208)` or `BgRgb(0, 0, 0)`, and an unknown
+/// parameter value is shown as is.
+impl Debug for GraphicSelection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let codes = self.modes.iter().flat_map(|mode| mode.split(';')).collect::<Vec<_>>();
+        let mut list = f.debug_list();
+        let mut i = 0;
+        while i < codes.len() {
+            let (name, len) = attribute_name(&codes[i..]);
+            list.entry(&format_args!("{}", name));
+            i += len;
+        }
+        list.finish()
+    }
+}
+
+/// Names the attribute at the start of `codes`, returning the name and the number of codes it spans.
+fn attribute_name(codes: &[&str]) -> (String, usize) {
+    let Ok(code) = codes[0].parse::<u16>() else { return (codes[0].to_string(), 1) };
+    let standard = |n: u16| format!("{:?}", Color::STANDARD[(n % 10) as usize]);
+    let name = match code {
+        0 => "Reset",
+        1 => "Bold",
+        2 => "Faint",
+        3 => "Italic",
+        4 => "Underline",
+        5 => "SlowBlink",
+        6 => "FastBlink",
+        7 => "Negative",
+        8 => "Conceal",
+        9 => "Cross",
+        10 => "PrimaryFont",
+        11..=19 => return (format!("AlterFont({})", code - 10), 1),
+        20 => "Fraktur",
+        21 => "DoubleUnderline",
+        22 => "NotBoldOrFaint",
+        23 => "NotItalic",
+        24 => "NotUnderline",
+        25 => "NotBlink",
+        27 => "NotNegative",
+        28 => "NotConceal",
+        29 => "NotCross",
+        30..=37 => return (format!("Fg{}", standard(code)), 1),
+        39 => "FgDefault",
+        40..=47 => return (format!("Bg{}", standard(code)), 1),
+        49 => "BgDefault",
+        51 => "Frame",
+        52 => "Encircle",
+        53 => "Overline",
+        54 => "NotFrameNotEncircle",
+        55 => "NotOverline",
+        59 => "DefaultUnderlineColor",
+        90..=97 => return (format!("FgBright{}", standard(code)), 1),
+        100..=107 => return (format!("BgBright{}", standard(code)), 1),
+        38 | 48 | 58 => {
+            let target = match code { 38 => "Fg", 48 => "Bg", _ => "UnderlineColor" };
+            return match codes.get(1..) {
+                Some(["5", n, ..]) => (format!("{}256({})", target, n), 3),
+                Some(["2", r, g, b, ..]) => (format!("{}Rgb({}, {}, {})", target, r, g, b), 5),
+                _ => (codes[0].to_string(), 1),
+            };
+        }
+        _ => return (codes[0].to_string(), 1),
+    };
+    (name.to_string(), 1)
+}
+
+impl From<GraphicSelection> for String {
+    fn from(graphic_selection: GraphicSelection) -> Self { graphic_selection.to_string() }
+}
+
+/// The SGR sequence establishing the default rendition, same as `select_graphic().default_rendition()` without allocating.
+pub const RESET: &str = "\x1b[0m";
+
+/// The SGR sequence selecting a bold rendition, same as `select_graphic().bold()` without allocating.
+pub const BOLD: &str = "\x1b[1m";
+
+/// The SGR sequence selecting a singly underlined rendition, same as `select_graphic().underline()` without
+/// allocating.
+pub const UNDERLINE: &str = "\x1b[4m";
+
 /// Format a string with the specified `SGR` sequence.
 ///
-/// The string is terminated with the sequence `\x1b[0m` to reset the style.
+/// The string is terminated with the sequence [RESET] (`\x1b[0m`) to reset the style.
 ///
 /// ### Example
 /// ```
@@ -836,7 +1343,21 @@ impl Display for GraphicSelection {
 /// println!("Hello {} !", formatted);
 /// ```
 pub fn format_str(str: &str, format: &GraphicSelection) -> String {
-    format!("{}{}{}", format, str, select_graphic().default())
+    format!("{}{}{}", format, str, RESET)
+}
+
+/// Format a string with the specified `SAPV` sequence.
+///
+/// The string is terminated with the default presentation variant (`\x1b[0 ]`), like [format_str] for SGR.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::{select_alternative, with_variant};
+/// let formatted = with_variant("1234", select_alternative().arabic_decimal());
+/// println!("Total: {}", formatted);
+/// ```
+pub fn with_variant(str: &str, variant: &PresentationVariant) -> String {
+    format!("{}{}{}", variant, str, select_alternative().default())
 }
 
 /// # SHS - Select character spacing
@@ -860,7 +1381,7 @@ pub enum CharacterSpacing {
 }
 
 impl Display for CharacterSpacing {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             CharacterSpacing::Per25mm10Chars => "0",
             CharacterSpacing::Per25mm12Chars => "1",
@@ -1063,7 +1584,7 @@ pub enum PrintQuality {
 }
 
 impl Display for PrintQuality {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             PrintQuality::Highest => "0",
             PrintQuality::Medium => "1",
@@ -1125,7 +1646,7 @@ pub enum StringReversion {
 }
 
 impl Display for StringReversion {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             Self::End => "0",
             Self::BeginReverse => "1",
@@ -1155,7 +1676,7 @@ pub enum SizeUnit {
 }
 
 impl Display for SizeUnit {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             SizeUnit::Character => "0",
             SizeUnit::Millimeter => "1",
@@ -1225,7 +1746,7 @@ pub enum LineSpacing {
 }
 
 impl Display for LineSpacing {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             LineSpacing::Per25mm6Lines => "0",
             LineSpacing::Per25mm4Lines => "1",
@@ -1266,6 +1787,8 @@ pub fn align_center(n: usize) -> ControlSequence {
 ///
 /// A text string aligned with a tabulation stop set by TALE will be positioned so that the (leading edge of
 /// the) last graphic character of the string is placed at the tabulation stop.
+///
+/// See [align_trailing] for TATE.
 pub fn align_leading(n: usize) -> ControlSequence {
     ControlSequence::new(&[&n.to_string()], " a")
 }
@@ -1280,6 +1803,8 @@ pub fn align_leading(n: usize) -> ControlSequence {
 ///
 /// A text string aligned with a tabulation stop set by TATE will be positioned so that the (trailing edge of
 /// the) first graphic character of the string is placed at the tabulation stop.
+///
+/// See [align_leading] for TALE.
 pub fn align_trailing(n: usize) -> ControlSequence {
     ControlSequence::new(&[&n.to_string()], " `")
 }
@@ -1315,4 +1840,247 @@ pub fn tabulation_center_on_char(l: usize, ascii: usize) -> ControlSequence {
 /// SIZE UNIT (SSU).
 pub fn specify_thin_space(width: usize) -> ControlSequence {
     ControlSequence::new(&[&width.to_string()], " E")
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::presentation::{add_separation, align_center, align_leading, align_trailing, character_combination, dimension_text, format_str, line_home, line_limit, line_spacing, modify_size, page_home, page_limit, reduce_separation, repeat, repeat_char, repeat_styled, select_size, select_tabulation, GraphicSelection, space_width, spacing_increment, specify_thin_space, tabulation_center_on_char, rgb_to_256, select_font, select_font_with_charset, select_graphic, select_alternative, sgr, with_variant, Color, Combination, Font, BOLD, RESET, UNDERLINE};
+
+    #[test]
+    fn graphic_codes() {
+        assert_eq!(select_graphic().fg_rgb(255, 128, 0).bold().codes(), vec![38, 2, 255, 128, 0, 1]);
+        assert_eq!(select_graphic().fg_rgb(255, 128, 0).bold().to_string(), "\x1b[38;2;255;128;0;1m");
+    }
+
+    #[test]
+    fn graphic_modes() {
+        assert_eq!(select_graphic().bold().underline().modes(), ["1", "4"]);
+        assert!(select_graphic().modes().is_empty());
+    }
+
+    #[test]
+    fn graphic_params_iter() {
+        let mut selection = select_graphic();
+        selection.bold().fg_rgb(255, 128, 0).underline();
+        assert_eq!(selection.params_iter().collect::<Vec<_>>(), vec!["1", "38;2;255;128;0", "4"]);
+        assert_eq!((&selection).into_iter().collect::<Vec<_>>().join(";"), "1;38;2;255;128;0;4");
+    }
+
+    #[test]
+    fn rgb_downsampling() {
+        assert_eq!(rgb_to_256(0, 0, 0), 16);
+        assert_eq!(rgb_to_256(255, 255, 255), 231);
+        assert_eq!(rgb_to_256(128, 128, 128), 244);
+        assert_eq!(rgb_to_256(255, 0, 0), 196);
+        assert_eq!(rgb_to_256(95, 135, 175), 67);
+        assert_eq!(Color::Rgb(128, 128, 128).to_256(), Some(244));
+        assert_eq!(Color::Bright(1).to_256(), Some(9));
+        assert_eq!(Color::Cyan.to_256(), Some(6));
+        assert_eq!(Color::Default.to_256(), None);
+    }
+
+    #[test]
+    fn constant_sequences() {
+        assert!(format_str("x", select_graphic().bold()).ends_with(RESET));
+        assert_eq!(RESET, select_graphic().default_rendition().to_string());
+        assert_eq!(BOLD, select_graphic().bold().to_string());
+        assert_eq!(UNDERLINE, select_graphic().underline().to_string());
+    }
+
+    #[test]
+    fn indexed_colors() {
+        assert_eq!(select_graphic().fg_color_256(208).to_string(), "\x1b[38;5;208m");
+        assert_eq!(select_graphic().bold().bg_color_256(17).to_string(), "\x1b[1;48;5;17m");
+        assert_eq!(select_graphic().fg_color_256(208).codes(), vec![38, 5, 208]);
+    }
+
+    #[test]
+    fn color_variants() {
+        let fg = |color: Color| select_graphic().fg(color).to_string();
+        let bg = |color: Color| select_graphic().bg(color).to_string();
+        assert_eq!(fg(Color::Black), "\x1b[30m");
+        assert_eq!(fg(Color::Gray), "\x1b[37m");
+        assert_eq!(bg(Color::Yellow), "\x1b[43m");
+        assert_eq!(fg(Color::Bright(1)), "\x1b[91m");
+        assert_eq!(bg(Color::Bright(7)), "\x1b[107m");
+        assert_eq!(fg(Color::Indexed(208)), "\x1b[38;5;208m");
+        assert_eq!(bg(Color::Indexed(17)), "\x1b[48;5;17m");
+        assert_eq!(fg(Color::Rgb(1, 2, 3)), "\x1b[38;2;1;2;3m");
+        assert_eq!(bg(Color::Rgb(1, 2, 3)), "\x1b[48;2;1;2;3m");
+        assert_eq!(fg(Color::Default), "\x1b[39m");
+        assert_eq!(bg(Color::Default), "\x1b[49m");
+        assert_eq!(select_graphic().fg(Color::Magenta).to_string(), select_graphic().fg_magenta().to_string());
+        assert_eq!(Color::from_index16(9), Color::Bright(1));
+    }
+
+    #[test]
+    fn color_presence() {
+        assert!(select_graphic().bg_red().has_background());
+        assert!(!select_graphic().fg_red().has_background());
+        assert!(select_graphic().fg_red().has_foreground());
+        assert!(select_graphic().bg_rgb(0, 0, 40).has_background());
+        assert!(!select_graphic().fg_color_256(41).has_background());
+        assert!(!select_graphic().bg_red().bg_default().has_background());
+    }
+
+    #[test]
+    fn underline_colors() {
+        assert_eq!(select_graphic().underline().underline_color_256(201).to_string(), "\x1b[4;58;5;201m");
+        assert_eq!(select_graphic().double_underline().underline_color_rgb(1, 2, 3).to_string(), "\x1b[21;58;2;1;2;3m");
+        assert_eq!(select_graphic().default_underline_color().to_string(), "\x1b[59m");
+    }
+
+    #[test]
+    fn bright_colors() {
+        assert_eq!(select_graphic().fg_bright_green().to_string(), "\x1b[92m");
+        assert_eq!(select_graphic().fg_bright_red().bold().bg_bright_gray().to_string(), "\x1b[91;1;107m");
+        assert_eq!(select_graphic().bg_bright_black().to_string(), select_graphic().bg_index16(8).to_string());
+        assert_eq!(select_graphic().fg(Color::Bright(9)).to_string(), "\x1b[91m");
+        assert_eq!(select_graphic().bg(Color::Bright(15)).to_string(), "\x1b[107m");
+        assert_eq!(Color::Bright(9).to_256(), Some(9));
+    }
+
+    #[test]
+    fn index16_colors() {
+        assert_eq!(select_graphic().fg_index16(1).to_string(), "\x1b[31m");
+        assert_eq!(select_graphic().fg_index16(9).to_string(), "\x1b[91m");
+        assert_eq!(select_graphic().bg_index16(0).bg_index16(15).to_string(), "\x1b[40;107m");
+        assert_eq!(select_graphic().fg_index16(16).to_string(), "\x1b[38;5;16m");
+        assert_eq!(select_graphic().bg_index16(16).to_string(), "\x1b[48;5;16m");
+        assert_eq!(Color::from_index16(16), Color::Indexed(16));
+    }
+
+    #[test]
+    fn graphic_font() {
+        assert_eq!(select_graphic().font(Font::Alternative2).to_string(), "\x1b[12m");
+        assert_eq!(select_graphic().font(Font::Primary).to_string(), select_graphic().primary_font().to_string());
+    }
+
+    #[test]
+    fn rendition_combination() {
+        assert_eq!(select_graphic().bold().assuming_cumulative().to_string(), "\x1b[1m");
+        assert_eq!(select_graphic().bold().assuming_replacing().to_string(), "\x1b[0;1m");
+        assert_eq!(select_graphic().default_rendition().bold().assuming_replacing().to_string(), "\x1b[0;1m");
+    }
+
+    #[test]
+    fn tabulation_alignment() {
+        // TALE
+        assert_eq!(align_leading(8).to_string(), "\x1b[8 a");
+        // TATE
+        assert_eq!(align_trailing(8).to_string(), "\x1b[8 `");
+    }
+
+    #[test]
+    fn presentation_finals() {
+        // Pinned against ECMA-48 (5th edition), section 8.3.
+        assert_eq!(dimension_text(1, 2).to_string(), "\x1b[1;2 T");
+        assert_eq!(modify_size(1, 2).to_string(), "\x1b[1;2 B");
+        assert_eq!(select_size(3).to_string(), "\x1b[3 C");
+        assert_eq!(repeat(3).to_string(), "\x1b[3b");
+        assert_eq!(add_separation(3).to_string(), "\x1b[3 \\");
+        assert_eq!(line_home(3).to_string(), "\x1b[3 U");
+        assert_eq!(line_limit(3).to_string(), "\x1b[3 V");
+        assert_eq!(line_spacing(3).to_string(), "\x1b[3 h");
+        assert_eq!(page_home(3).to_string(), "\x1b[3 i");
+        assert_eq!(page_limit(3).to_string(), "\x1b[3 j");
+        assert_eq!(spacing_increment(1, 2).to_string(), "\x1b[1;2 G");
+        assert_eq!(reduce_separation(3).to_string(), "\x1b[3 f");
+        assert_eq!(space_width(3).to_string(), "\x1b[3 [");
+        assert_eq!(select_tabulation(3).to_string(), "\x1b[3 ^");
+        assert_eq!(align_center(3).to_string(), "\x1b[3 b");
+        assert_eq!(tabulation_center_on_char(1, 46).to_string(), "\x1b[1;46 c");
+        assert_eq!(specify_thin_space(3).to_string(), "\x1b[3 E");
+        assert_eq!(character_combination(Combination::Start).to_string(), "\x1b[1 _");
+    }
+
+    #[test]
+    fn variant_wrapping() {
+        assert_eq!(with_variant("42", select_alternative().latin_decimal()), "\x1b[1 ]42\x1b[0 ]");
+        assert_eq!(with_variant("", select_alternative().latin_decimal().mirror_horizontal()), "\x1b[1;3 ]\x1b[0 ]");
+    }
+
+    #[test]
+    fn repeat_graphic_char() {
+        assert_eq!(repeat_char('=', 5), "=\x1b[4b");
+        assert_eq!(repeat_char('x', 2), "x\x1b[1b");
+        assert_eq!(repeat_char('x', 0), "");
+        assert_eq!(repeat_char('\n', 3), "\n\n\n");
+    }
+
+    #[test]
+    fn repeat_styled_char() {
+        let red = select_graphic().fg_red().clone();
+        let stars = repeat_styled('*', 3, &red);
+        assert_eq!(stars, "\x1b[31m*\x1b[2b\x1b[0m");
+        assert_eq!(stars.matches("\x1b[31m").count(), 1);
+        assert_eq!(repeat_styled('*', 1, &red), "\x1b[31m*\x1b[0m");
+        assert_eq!(repeat_styled('*', 0, &red), "");
+    }
+
+    #[test]
+    fn attribute_resets() {
+        assert_eq!(select_graphic().reset_intensity().to_string(), "\x1b[22m");
+        assert_eq!(select_graphic().reset_italic().to_string(), "\x1b[23m");
+        assert_eq!(select_graphic().reset_underline().to_string(), "\x1b[24m");
+        assert_eq!(select_graphic().reset_blink().to_string(), "\x1b[25m");
+        assert_eq!(select_graphic().reset_inverse().to_string(), "\x1b[27m");
+        assert_eq!(select_graphic().reset_conceal().to_string(), "\x1b[28m");
+        assert_eq!(select_graphic().reset_strike().to_string(), "\x1b[29m");
+        assert_eq!(select_graphic().reset_fg().to_string(), "\x1b[39m");
+        assert_eq!(select_graphic().reset_bg().to_string(), "\x1b[49m");
+    }
+
+    #[test]
+    fn selection_from_codes() {
+        assert_eq!(GraphicSelection::from_codes(&["1", "31"]).get().to_string(), "\x1b[1;31m");
+        assert_eq!(GraphicSelection::from(&["4"][..]).to_string(), "\x1b[4m");
+        assert!(GraphicSelection::default().modes().is_empty());
+        assert_eq!(GraphicSelection::default(), GraphicSelection::new());
+        assert_eq!(GraphicSelection::default().default_rendition().to_string(), "\x1b[0m");
+    }
+
+    #[test]
+    fn raw_sgr() {
+        assert_eq!(sgr(&["1", "38", "5", "200"]).to_string(), "\x1b[1;38;5;200m");
+        assert_eq!(sgr(&[]).to_string(), "\x1b[m");
+    }
+
+    #[test]
+    fn selections_hash() {
+        use std::collections::HashSet;
+
+        let mut styles = HashSet::new();
+        styles.insert(select_graphic().bold().fg_red().clone());
+        styles.insert(GraphicSelection::from_codes(&["1", "31"]));
+        assert_eq!(styles.len(), 1);
+        assert!(select_graphic().bold().get() != select_graphic().faint().get());
+    }
+
+    #[test]
+    fn selection_into_string() {
+        let bold = select_graphic().bold().clone();
+        assert_eq!(bold.to_sequence_string(), "\x1b[1m");
+        assert_eq!(String::from(bold), "\x1b[1m");
+    }
+
+    #[test]
+    fn selection_debug() {
+        assert_eq!(format!("{:?}", select_graphic().bold().fg_red()), "[Bold, FgRed]");
+        assert_eq!(format!("{:?}", select_graphic().fg_color_256(208).bg_rgb(0, 0, 0).bg_bright_cyan()), "[Fg256(208), BgRgb(0, 0, 0), BgBrightCyan]");
+        assert_eq!(format!("{:?}", GraphicSelection::from_codes(&["4", "58", "5", "1", "99"])), "[Underline, UnderlineColor256(1), 99]");
+        assert_eq!(format!("{:?}", GraphicSelection::new()), "[]");
+    }
+
+    #[test]
+    fn font_to_sgr() {
+        assert_eq!(GraphicSelection::new().font(Font::Alternative3).to_string(), "\x1b[13m");
+        assert_eq!(Font::Primary.to_sgr(), "10");
+        assert_eq!(Font::Alternative9.to_sgr(), "19");
+    }
+
+    #[test]
+    fn font_charset() {
+        assert_eq!(select_font_with_charset(Font::Alternative1, 2).to_string(), "\x1b[1;2 D");
+        assert_eq!(select_font(Font::Alternative1).to_string(), "\x1b[1;0 D");
+    }
+}
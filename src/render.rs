@@ -0,0 +1,107 @@
+//! This module helps emit the control functions in their 7-bit or 8-bit form.
+//!
+//! Every function of this crate builds the 7-bit form, where a C1 control is represented by **ESC** followed
+//! by a byte of the range `0x40` to `0x5F` (see ECMA-35). A [Renderer] rewrites a built stream into the form
+//! required by the environment.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::introducers::ESC;
+
+/// The representation of the C1 controls, like CSI, OSC, DCS, SS2, SS3 or ST.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ReprMode {
+    /// **ESC** followed by a byte of the range `0x40` to `0x5F`, like `\x1b[` for CSI.
+    #[default]
+    SevenBit,
+    /// A single C1 character of the range `0x80` to `0x9F`, like `\u{9B}` for CSI.
+    EightBit,
+}
+
+/// Rewrites the C1 controls of a stream into a [ReprMode].
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::set_position;
+/// use coded_chars::render::{Renderer, ReprMode};
+///
+/// let renderer = Renderer::new(ReprMode::EightBit);
+/// assert_eq!(renderer.render(&set_position(1, 1).to_string()), "\u{9B}1;1H");
+/// assert_eq!(renderer.render_bytes(&set_position(1, 1).to_string()), b"\x9B1;1H");
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Renderer {
+    mode: ReprMode,
+}
+
+impl Renderer {
+    pub fn new(mode: ReprMode) -> Self { Self { mode } }
+
+    pub fn mode(&self) -> ReprMode { self.mode }
+
+    /// Returns `input` with its C1 controls in the mode of this renderer.
+    ///
+    /// The 8-bit controls are `char`s of the range `\u{80}` to `\u{9F}`, encoded in UTF-8 in the returned
+    /// string, see [Renderer::render_bytes] for single bytes.
+    pub fn render(&self, input: &str) -> String {
+        let mut output = String::with_capacity(input.len());
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (self.mode, c) {
+                (ReprMode::EightBit, ESC) => match chars.peek() {
+                    Some(&next @ '\x40'..='\x5F') => {
+                        chars.next();
+                        output.push(char::from(next as u8 + 0x40));
+                    }
+                    _ => output.push(c),
+                },
+                (ReprMode::SevenBit, '\u{80}'..='\u{9F}') => {
+                    output.push(ESC);
+                    output.push(char::from(c as u8 - 0x40));
+                }
+                _ => output.push(c),
+            }
+        }
+        output
+    }
+
+    /// Same as [Renderer::render], but encodes the 8-bit controls as single bytes, the other characters
+    /// being encoded in UTF-8.
+    pub fn render_bytes(&self, input: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(input.len());
+        for c in self.render(input).chars() {
+            match c {
+                '\u{80}'..='\u{9F}' => bytes.push(c as u8),
+                _ => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cursor::set_position;
+    use crate::delimiters::{OSC, ST};
+    use crate::presentation::{format_str, select_graphic};
+    use crate::render::{Renderer, ReprMode};
+    use crate::shifts::{SS2, SS3};
+
+    #[test]
+    fn mixed_stream_round_trip() {
+        let stream = format!(
+            "{}{}{}0;title{}{}a{}b\x1b(B",
+            set_position(2, 1), format_str("caf\u{e9}", select_graphic().bold()), OSC, ST, SS2, SS3
+        );
+        let eight_bit = Renderer::new(ReprMode::EightBit).render(&stream);
+        assert_eq!(eight_bit, "\u{9B}2;1H\u{9B}1mcaf\u{e9}\u{9B}0m\u{9D}0;title\u{9C}\u{8E}a\u{8F}b\x1b(B");
+        assert_eq!(Renderer::new(ReprMode::SevenBit).render(&eight_bit), stream);
+        assert_eq!(Renderer::default().render(&stream), stream);
+    }
+
+    #[test]
+    fn single_bytes() {
+        let bytes = Renderer::new(ReprMode::EightBit).render_bytes("\x1b[1m\u{e9}\x1b\\");
+        assert_eq!(bytes, b"\x9B1m\xC3\xA9\x9C");
+    }
+}
@@ -0,0 +1,292 @@
+//! This module helps switch to the alternate screen buffer and restore the terminal state on exit.
+//!
+//! The alternate screen is a DEC private mode (`?1049`) widely implemented by terminals, it is not part of
+//! ECMA-48.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::Write;
+use crate::control::ControlSequence;
+use crate::cursor;
+use crate::display;
+use crate::mode::Mode;
+use crate::mouse::{self, MouseMode};
+use crate::presentation::RESET;
+
+/// Switches to the alternate screen buffer, saving the cursor position.
+///
+/// - The printed sequence is : `\x1b[?1049h`
+pub fn enter_alternate() -> ControlSequence {
+    ControlSequence::private(&["1049"], "h")
+}
+
+/// Switches back to the normal screen buffer, restoring the cursor position.
+///
+/// - The printed sequence is : `\x1b[?1049l`
+pub fn leave_alternate() -> ControlSequence {
+    ControlSequence::private(&["1049"], "l")
+}
+
+/// Runs `f` on the alternate screen with the cursor hidden.
+///
+/// The normal screen and the cursor are always restored afterward, even if `f` returns an error. The error
+/// returned by `f` takes precedence over any error raised while restoring.
+///
+/// ### Example
+/// ```
+/// use std::io::{stdout, Write};
+/// use coded_chars::screen::with_fullscreen;
+///
+/// with_fullscreen(&mut stdout(), |out| {
+///     write!(out, "Hello from the alternate screen !")
+/// }).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn with_fullscreen<W: Write, F: FnOnce(&mut W) -> io::Result<()>>(w: &mut W, f: F) -> io::Result<()> {
+    write!(w, "{}{}", enter_alternate(), cursor::hide())?;
+    let result = f(w);
+    let restored = write!(w, "{}{}", cursor::show(), leave_alternate())
+        .and_then(|_| w.flush());
+    result.and(restored)
+}
+
+/// The parts of the terminal state restored by [teardown_seq].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TeardownOptions {
+    /// Shows the cursor, see [cursor::show].
+    pub show_cursor: bool,
+    /// Switches back to the normal screen buffer, see [leave_alternate].
+    pub leave_alternate: bool,
+    /// Disables every mouse tracking mode, see [mouse::disable].
+    pub disable_mouse: bool,
+    /// Resets the graphic rendition, see [RESET].
+    pub reset_graphic: bool,
+}
+
+impl TeardownOptions {
+    /// Restores every part of the terminal state.
+    pub fn all() -> Self {
+        Self { show_cursor: true, leave_alternate: true, disable_mouse: true, reset_graphic: true }
+    }
+}
+
+/// Returns the sequences restoring the terminal state selected by `options`, typically printed on exit.
+///
+/// Unlike [crate::device::RIS], only the selected parts are reset. The mouse is released first, so no more
+/// reports are received, and the normal screen is restored last.
+///
+/// ### Example
+/// ```
+/// use coded_chars::screen::{teardown_seq, TeardownOptions};
+///
+/// assert_eq!(
+///     teardown_seq(TeardownOptions { show_cursor: true, leave_alternate: true, ..Default::default() }),
+///     "\x1b[?25h\x1b[?1049l"
+/// );
+/// ```
+pub fn teardown_seq(options: TeardownOptions) -> String {
+    let mut output = String::new();
+    if options.disable_mouse {
+        for mode in [MouseMode::AnyMotion, MouseMode::Drag, MouseMode::Click, MouseMode::SgrExtended] {
+            output.push_str(&mouse::disable(mode).to_string());
+        }
+    }
+    if options.reset_graphic {
+        output.push_str(RESET);
+    }
+    if options.show_cursor {
+        output.push_str(&cursor::show().to_string());
+    }
+    if options.leave_alternate {
+        output.push_str(&leave_alternate().to_string());
+    }
+    output
+}
+
+/// Records the changes made to the terminal state, to undo them with [full_reset_seq].
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor;
+/// use coded_chars::screen::{full_reset_seq, RenderState};
+///
+/// let mut state = RenderState::new();
+/// print!("{}", cursor::hide());
+/// state.cursor_hidden();
+///
+/// // On exit
+/// assert_eq!(full_reset_seq(&state), "\x1b[?25h");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RenderState {
+    graphic: bool,
+    cursor_hidden: bool,
+    alternate: bool,
+    mouse: Vec<MouseMode>,
+    scroll_region: bool,
+    origin_mode: bool,
+    /// Parameters of the ECMA-48 modes set with SM, in the order they were set.
+    modes: Vec<String>,
+}
+
+impl RenderState {
+    pub fn new() -> Self { Self::default() }
+
+    /// Records that the graphic rendition was changed (SGR).
+    pub fn graphic(&mut self) -> &mut Self {
+        self.graphic = true;
+        self
+    }
+
+    /// Records that the cursor was hidden, see [cursor::hide].
+    pub fn cursor_hidden(&mut self) -> &mut Self {
+        self.cursor_hidden = true;
+        self
+    }
+
+    /// Records that the alternate screen buffer was entered, see [enter_alternate].
+    pub fn alternate_screen(&mut self) -> &mut Self {
+        self.alternate = true;
+        self
+    }
+
+    /// Records that the mouse tracking `mode` was enabled, see [mouse::enable].
+    pub fn mouse(&mut self, mode: MouseMode) -> &mut Self {
+        if !self.mouse.contains(&mode) {
+            self.mouse.push(mode);
+        }
+        self
+    }
+
+    /// Records that a scrolling region was set, see [display::set_scroll_region].
+    pub fn scroll_region(&mut self) -> &mut Self {
+        self.scroll_region = true;
+        self
+    }
+
+    /// Records that the origin mode was set, see [display::enable_origin_mode].
+    pub fn origin_mode(&mut self) -> &mut Self {
+        self.origin_mode = true;
+        self
+    }
+
+    /// Records that the ECMA-48 `modes` were set with SM, see [Mode::set].
+    pub fn modes(&mut self, modes: &Mode) -> &mut Self {
+        for parameter in modes.params_iter() {
+            if !self.modes.iter().any(|mode| mode == parameter) {
+                self.modes.push(parameter.to_string());
+            }
+        }
+        self
+    }
+}
+
+/// Returns the sequences bringing back to their defaults the parts of the terminal state recorded in `state`.
+///
+/// Nothing is emitted for the parts left unchanged. The mouse modes are disabled first, in the reverse order
+/// they were enabled, and the normal screen is restored last, like with [teardown_seq]. Each recorded
+/// ECMA-48 mode is reset with its own RM, in the reverse order they were set, the reset state being the
+/// default state of the modes.
+///
+/// ### Example
+/// ```
+/// use coded_chars::mouse::MouseMode;
+/// use coded_chars::screen::{full_reset_seq, RenderState};
+///
+/// let state = RenderState::new().graphic().mouse(MouseMode::Click).alternate_screen().clone();
+/// assert_eq!(full_reset_seq(&state), "\x1b[?1000l\x1b[0m\x1b[?1049l");
+/// ```
+pub fn full_reset_seq(state: &RenderState) -> String {
+    let mut output = String::new();
+    for mode in state.mouse.iter().rev() {
+        output.push_str(&mouse::disable(*mode).to_string());
+    }
+    if state.origin_mode {
+        output.push_str(&display::disable_origin_mode().to_string());
+    }
+    if state.scroll_region {
+        output.push_str(&display::reset_scroll_region().to_string());
+    }
+    for mode in state.modes.iter().rev() {
+        output.push_str(&ControlSequence::new(&[mode], "l").to_string());
+    }
+    if state.graphic {
+        output.push_str(RESET);
+    }
+    if state.cursor_hidden {
+        output.push_str(&cursor::show().to_string());
+    }
+    if state.alternate {
+        output.push_str(&leave_alternate().to_string());
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    use std::io::{Error, Write};
+    use crate::mode::mode;
+    use crate::mouse::MouseMode;
+    use crate::screen::{full_reset_seq, teardown_seq, RenderState, TeardownOptions};
+    #[cfg(feature = "std")]
+    use crate::screen::with_fullscreen;
+
+    #[test]
+    fn teardown_combinations() {
+        const MOUSE: &str = "\x1b[?1003l\x1b[?1002l\x1b[?1000l\x1b[?1006l";
+        for bits in 0..16u8 {
+            let options = TeardownOptions {
+                disable_mouse: bits & 1 != 0,
+                reset_graphic: bits & 2 != 0,
+                show_cursor: bits & 4 != 0,
+                leave_alternate: bits & 8 != 0,
+            };
+            let expected = [(1, MOUSE), (2, "\x1b[0m"), (4, "\x1b[?25h"), (8, "\x1b[?1049l")]
+                .iter()
+                .filter(|(bit, _)| bits & bit != 0)
+                .map(|(_, sequence)| *sequence)
+                .collect::<String>();
+            assert_eq!(teardown_seq(options), expected);
+        }
+        assert_eq!(teardown_seq(TeardownOptions::all()), format!("{}\x1b[0m\x1b[?25h\x1b[?1049l", MOUSE));
+    }
+
+    #[test]
+    fn reset_recorded_changes() {
+        assert_eq!(full_reset_seq(&RenderState::new()), "");
+        assert_eq!(full_reset_seq(RenderState::new().graphic().cursor_hidden()), "\x1b[0m\x1b[?25h");
+        assert_eq!(
+            full_reset_seq(RenderState::new().mouse(MouseMode::Drag).mouse(MouseMode::SgrExtended).mouse(MouseMode::Drag)),
+            "\x1b[?1006l\x1b[?1002l"
+        );
+        assert_eq!(full_reset_seq(RenderState::new().origin_mode().scroll_region()), "\x1b[?6l\x1b[r");
+    }
+
+    #[test]
+    fn reset_recorded_modes() {
+        let mut state = RenderState::new();
+        state.modes(mode().insertion_replacement().line_editing()).modes(mode().insertion_replacement()).graphic();
+        assert_eq!(full_reset_seq(&state), "\x1b[7l\x1b[4l\x1b[0m");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn fullscreen_brackets_output() {
+        let mut out = Vec::new();
+        with_fullscreen(&mut out, |w| write!(w, "hello")).unwrap();
+        assert_eq!(out, b"\x1b[?1049h\x1b[?25lhello\x1b[?25h\x1b[?1049l");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn fullscreen_restores_on_error() {
+        let mut out = Vec::new();
+        let result = with_fullscreen(&mut out, |_| Err(Error::other("failed")));
+        assert!(result.is_err());
+        assert_eq!(out, b"\x1b[?1049h\x1b[?25l\x1b[?25h\x1b[?1049l");
+    }
+}
@@ -0,0 +1,269 @@
+//! This module resolves SGR parameters into the graphic rendition they establish.
+//!
+//! It helps compute the shortest SGR sequence moving a device from a rendition to another, assuming the
+//! GRAPHIC RENDITION COMBINATION MODE (GRCM) is set to CUMULATIVE.
+//!
+//! A renderer can keep the [SgrState] of the device and emit only the changed aspects with [SgrState::diff].
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::vec;
+use crate::control::ControlSequence;
+use crate::presentation::GraphicSelection;
+
+/// The graphic rendition aspects established by a list of SGR parameters.
+///
+/// The default state is the default rendition.
+///
+/// ### Example
+/// ```
+/// use coded_chars::sgr::SgrState;
+///
+/// let current = SgrState::from_codes(&[1, 4, 31]);
+/// let next = SgrState::from_codes(&[1, 32]);
+/// assert_eq!(current.diff(&next).to_string(), "\x1b[24;32m");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SgrState {
+    /// Bold (`1`) or faint (`2`).
+    intensity: Option<u16>,
+    /// Italicized (`3`) or Fraktur (`20`).
+    italic: Option<u16>,
+    /// Singly (`4`) or doubly (`21`) underlined.
+    underline: Option<u16>,
+    /// Slowly (`5`) or rapidly (`6`) blinking.
+    blink: Option<u16>,
+    negative: bool,
+    conceal: bool,
+    crossed: bool,
+    /// Alternative fonts `11` to `19`, the primary font being `None`.
+    font: Option<u16>,
+    foreground: Option<Vec<u16>>,
+    background: Option<Vec<u16>>,
+    underline_color: Option<Vec<u16>>,
+    /// Framed (`51`) or encircled (`52`).
+    framing: Option<u16>,
+    overline: bool,
+    /// Ideogram underline, overline or stress marking (`60` to `64`).
+    ideogram: Option<u16>,
+}
+
+impl SgrState {
+    /// Resolves the rendition established by `codes` from the default rendition.
+    pub fn from_codes(codes: &[u16]) -> Self {
+        let mut state = Self::default();
+        state.apply(codes);
+        state
+    }
+
+    /// Applies the SGR parameters `codes` to this rendition.
+    pub fn apply(&mut self, codes: &[u16]) {
+        let mut i = 0;
+        while i < codes.len() {
+            let code = codes[i];
+            match code {
+                0 => *self = Self::default(),
+                1 | 2 => self.intensity = Some(code),
+                3 | 20 => self.italic = Some(code),
+                4 | 21 => self.underline = Some(code),
+                5 | 6 => self.blink = Some(code),
+                7 => self.negative = true,
+                8 => self.conceal = true,
+                9 => self.crossed = true,
+                10 => self.font = None,
+                11..=19 => self.font = Some(code),
+                22 => self.intensity = None,
+                23 => self.italic = None,
+                24 => self.underline = None,
+                25 => self.blink = None,
+                27 => self.negative = false,
+                28 => self.conceal = false,
+                29 => self.crossed = false,
+                30..=37 | 90..=97 => self.foreground = Some(vec![code]),
+                39 => self.foreground = None,
+                40..=47 | 100..=107 => self.background = Some(vec![code]),
+                49 => self.background = None,
+                38 | 48 | 58 => {
+                    let extended = extended_color(&codes[i..]);
+                    i += extended.len().max(1) - 1;
+                    if !extended.is_empty() {
+                        match code {
+                            38 => self.foreground = Some(extended),
+                            48 => self.background = Some(extended),
+                            _ => self.underline_color = Some(extended),
+                        }
+                    }
+                }
+                51 | 52 => self.framing = Some(code),
+                53 => self.overline = true,
+                54 => self.framing = None,
+                55 => self.overline = false,
+                59 => self.underline_color = None,
+                60..=64 => self.ideogram = Some(code),
+                65 => self.ideogram = None,
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    /// Returns whether a foreground color other than the default one is established.
+    pub fn has_foreground(&self) -> bool { self.foreground.is_some() }
+
+    /// Returns whether a background color other than the default one is established.
+    pub fn has_background(&self) -> bool { self.background.is_some() }
+
+    /// Returns the SGR parameters establishing this rendition from the default rendition.
+    pub fn codes(&self) -> Vec<u16> {
+        self.diff_codes(&Self::default())
+    }
+
+    /// Returns the SGR parameters moving a device from the rendition `from` to this rendition.
+    fn diff_codes(&self, from: &Self) -> Vec<u16> {
+        let mut codes = vec![];
+        let exclusive = |codes: &mut Vec<u16>, from: Option<u16>, to: Option<u16>, cancel: u16| {
+            if from != to {
+                if from.is_some() {
+                    codes.push(cancel);
+                }
+                codes.extend(to);
+            }
+        };
+        let switch = |codes: &mut Vec<u16>, from: Option<u16>, to: Option<u16>, cancel: u16| {
+            if from != to {
+                codes.push(to.unwrap_or(cancel));
+            }
+        };
+        let flag = |codes: &mut Vec<u16>, from: bool, to: bool, set: u16, cancel: u16| {
+            if from != to {
+                codes.push(if to { set } else { cancel });
+            }
+        };
+        let color = |codes: &mut Vec<u16>, from: &Option<Vec<u16>>, to: &Option<Vec<u16>>, cancel: u16| {
+            if from != to {
+                match to {
+                    Some(color) => codes.extend(color),
+                    None => codes.push(cancel),
+                }
+            }
+        };
+
+        exclusive(&mut codes, from.intensity, self.intensity, 22);
+        exclusive(&mut codes, from.italic, self.italic, 23);
+        switch(&mut codes, from.underline, self.underline, 24);
+        switch(&mut codes, from.blink, self.blink, 25);
+        flag(&mut codes, from.negative, self.negative, 7, 27);
+        flag(&mut codes, from.conceal, self.conceal, 8, 28);
+        flag(&mut codes, from.crossed, self.crossed, 9, 29);
+        switch(&mut codes, from.font, self.font, 10);
+        color(&mut codes, &from.foreground, &self.foreground, 39);
+        color(&mut codes, &from.background, &self.background, 49);
+        switch(&mut codes, from.framing, self.framing, 54);
+        flag(&mut codes, from.overline, self.overline, 53, 55);
+        color(&mut codes, &from.underline_color, &self.underline_color, 59);
+        switch(&mut codes, from.ideogram, self.ideogram, 65);
+        codes
+    }
+
+    /// Returns the shortest SGR moving a device from this rendition to the rendition `next`.
+    ///
+    /// Aspects which are set in this rendition but not in `next` are cancelled (`22`, `23`, `24`, `39`, `49`...),
+    /// new aspects are set. A default rendition (`0`) is used instead when it gives a shorter sequence.
+    ///
+    /// The returned selection is empty if both renditions are the same : it must not be printed then, as an
+    /// SGR without parameters is a default rendition.
+    pub fn diff(&self, next: &SgrState) -> GraphicSelection {
+        selection(&next.transition_codes(self))
+    }
+
+    /// Returns the shortest SGR parameters moving a device from the rendition `from` to this rendition.
+    ///
+    /// Returns an empty list if both renditions are the same.
+    pub(crate) fn transition_codes(&self, from: &Self) -> Vec<u16> {
+        let cancelling = self.diff_codes(from);
+        let resetting = core::iter::once(0).chain(self.codes()).collect::<Vec<_>>();
+        if cancelling.len() <= resetting.len() { cancelling } else { resetting }
+    }
+}
+
+/// Builds a [GraphicSelection] from SGR parameters, keeping the parameters of each extended color together.
+pub(crate) fn selection(codes: &[u16]) -> GraphicSelection {
+    let mut graphic_selection = GraphicSelection::new();
+    let mut i = 0;
+    while i < codes.len() {
+        let extended = match codes[i] {
+            38 | 48 | 58 => extended_color(&codes[i..]),
+            _ => vec![],
+        };
+        let group = if extended.is_empty() { &codes[i..=i] } else { &extended[..] };
+        graphic_selection.add(&group.iter().map(u16::to_string).collect::<Vec<_>>().join(";"));
+        i += group.len();
+    }
+    graphic_selection
+}
+
+/// Returns the parameters of the extended color (`38`, `48` or `58`) at the start of `codes`, or an empty list if
+/// the color is incomplete.
+fn extended_color(codes: &[u16]) -> Vec<u16> {
+    let len = match codes.get(1) {
+        Some(5) => 3,
+        Some(2) => 5,
+        _ => return vec![],
+    };
+    codes.get(..len).map_or(vec![], <[u16]>::to_vec)
+}
+
+/// Returns the shortest SGR sequence moving a device from the rendition established by the parameters
+/// `from` to the rendition established by the parameters `to`.
+///
+/// Aspects which are set in `from` but not in `to` are cancelled (`22`, `24`, `39`...), new aspects are set.
+/// A default rendition (`0`) is used instead when it gives a shorter sequence.
+///
+/// As an SGR without parameters is a default rendition, the sequence `0` followed by all the parameters of
+/// `to` is returned when both renditions are the same.
+///
+/// ### Example
+/// ```
+/// use coded_chars::sgr::transition_sgr;
+///
+/// // Turns bold off and changes the foreground color from red to green.
+/// assert_eq!(transition_sgr(&[1, 31], &[32]).to_string(), "\x1b[22;32m");
+/// ```
+pub fn transition_sgr(from: &[u16], to: &[u16]) -> ControlSequence {
+    let to = SgrState::from_codes(to);
+    let mut codes = to.transition_codes(&SgrState::from_codes(from));
+    if codes.is_empty() {
+        codes = core::iter::once(0).chain(to.codes()).collect();
+    }
+    let codes = codes.iter().map(u16::to_string).collect::<Vec<_>>();
+    ControlSequence::new(&codes.iter().map(String::as_str).collect::<Vec<_>>(), "m")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sgr::{transition_sgr, SgrState};
+
+    #[test]
+    fn transitions() {
+        assert_eq!(transition_sgr(&[1, 31], &[32]).to_string(), "\x1b[22;32m");
+        assert_eq!(transition_sgr(&[1], &[2]).to_string(), "\x1b[22;2m");
+        assert_eq!(transition_sgr(&[4, 38, 5, 208], &[4, 48, 2, 1, 2, 3]).to_string(), "\x1b[39;48;2;1;2;3m");
+        assert_eq!(transition_sgr(&[1, 3, 4, 7, 31], &[]).to_string(), "\x1b[0m");
+        assert_eq!(transition_sgr(&[1, 3, 4, 7], &[32]).to_string(), "\x1b[0;32m");
+        assert_eq!(transition_sgr(&[1], &[1]).to_string(), "\x1b[0;1m");
+        assert_eq!(transition_sgr(&[4, 58, 5, 201], &[4]).to_string(), "\x1b[59m");
+    }
+
+    #[test]
+    fn state_diff() {
+        let plain = SgrState::default();
+        let bold = SgrState::from_codes(&[1]);
+        assert_eq!(plain.diff(&bold).to_string(), "\x1b[1m");
+
+        let underlined = SgrState::from_codes(&[1, 4, 31]);
+        assert_eq!(underlined.diff(&SgrState::from_codes(&[1, 31])).to_string(), "\x1b[24m");
+        assert_eq!(underlined.diff(&SgrState::from_codes(&[1, 4, 38, 5, 208])).to_string(), "\x1b[38;5;208m");
+        assert_eq!(underlined.diff(&SgrState::from_codes(&[1, 4])).to_string(), "\x1b[39m");
+        assert!(bold.diff(&bold).modes().is_empty());
+    }
+}
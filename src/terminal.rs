@@ -0,0 +1,423 @@
+//! This module emulates a minimal terminal, to test the output of a program without a real device.
+//!
+//! A [TestTerminal] interprets the characters written to it and keeps the resulting grid of cells, the
+//! cursor position and the scrolling region. It is available in the tests of this crate, and to other
+//! crates with the `test-terminal` feature.
+//!
+//! Only the most common control functions are interpreted, the others are ignored :
+//! - the format effectors BS, HT, LF, VT, FF and CR,
+//! - the cursor control functions CUU, CUD, CUF, CUB, CNL, CPL, CHA, HPA, VPA, CUP and HVP,
+//! - the editor functions ED, EL, ICH, DCH, IL, DL and ECH,
+//! - the scrolling functions SU, SD, IND, NEL, RI and DECSTBM,
+//! - SGR, DECSC, DECRC and RIS.
+//!
+//! Every character occupies one cell. Lines are wrapped when a character is printed past the last column.
+//!
+//! ### Example
+//! ```
+//! use coded_chars::cursor::set_position;
+//! use coded_chars::presentation::{format_str, select_graphic};
+//! use coded_chars::terminal::TestTerminal;
+//!
+//! let mut terminal = TestTerminal::new(24, 80);
+//! terminal.feed(&format!("{}{}", set_position(1, 1), format_str("X", select_graphic().fg_red())));
+//!
+//! let cell = terminal.cell(1, 1).unwrap();
+//! assert_eq!(cell.character(), 'X');
+//! assert_eq!(cell.codes(), vec![31]);
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::vec;
+use std::io;
+use std::io::Write;
+use crate::format::{BS, CR, FF, HT, LF, VT};
+use crate::parser::{classify, csi_parts, validate, SequenceKind, ValidationError};
+use crate::presentation::GraphicSelection;
+use crate::sgr::{selection, SgrState};
+
+/// A character position of a [TestTerminal], with the graphic rendition it was printed with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cell {
+    character: Option<char>,
+    style: SgrState,
+}
+
+impl Cell {
+    /// Returns the character of this cell, a space if nothing was printed in it.
+    pub fn character(&self) -> char { self.character.unwrap_or(' ') }
+
+    /// Returns the SGR parameters establishing the rendition of this cell from the default rendition.
+    pub fn codes(&self) -> Vec<u16> { self.style.codes() }
+
+    /// Returns the graphic rendition of this cell.
+    pub fn style(&self) -> GraphicSelection { selection(&self.codes()) }
+
+    fn blank() -> Self { Self::default() }
+}
+
+/// A grid of cells interpreting the control functions written to it.
+///
+/// Lines and columns are numbered from 1, like in the control functions.
+pub struct TestTerminal {
+    rows: usize,
+    columns: usize,
+    grid: Vec<Vec<Cell>>,
+    /// Current line and column, from 0. The column equals `columns` after a character was printed in the
+    /// last column, the line being wrapped with the next character.
+    cursor: (usize, usize),
+    style: SgrState,
+    /// First and last lines of the scrolling region, from 0.
+    region: (usize, usize),
+    saved: Option<((usize, usize), SgrState)>,
+    /// Incomplete input, waiting for the rest of a character or a sequence.
+    pending: Vec<u8>,
+}
+
+/// Another name of [TestTerminal], for the code modelling a terminal outside of tests.
+pub type TerminalModel = TestTerminal;
+
+impl TestTerminal {
+    /// Creates a blank terminal of `rows` lines and `columns` columns, both at least 1.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        let (rows, columns) = (rows.max(1), columns.max(1));
+        Self {
+            rows,
+            columns,
+            grid: vec![vec![Cell::blank(); columns]; rows],
+            cursor: (0, 0),
+            style: SgrState::default(),
+            region: (0, rows - 1),
+            saved: None,
+            pending: vec![],
+        }
+    }
+
+    /// Returns the number of lines and columns.
+    pub fn size(&self) -> (usize, usize) { (self.rows, self.columns) }
+
+    /// Returns the cell at line `row` and column `column`, or `None` outside of the terminal.
+    pub fn cell(&self, row: usize, column: usize) -> Option<&Cell> {
+        self.grid.get(row.checked_sub(1)?)?.get(column.checked_sub(1)?)
+    }
+
+    /// Returns the line and column of the cursor.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.cursor.0 + 1, self.cursor.1.min(self.columns - 1) + 1)
+    }
+
+    /// Returns the graphic rendition the next character will be printed with.
+    pub fn current_style(&self) -> GraphicSelection { selection(&self.style.codes()) }
+
+    /// Returns the first and last lines of the scrolling region.
+    pub fn scroll_region(&self) -> (usize, usize) { (self.region.0 + 1, self.region.1 + 1) }
+
+    /// Returns the characters of the line `row`, without trailing spaces.
+    pub fn row_text(&self, row: usize) -> Option<String> {
+        let line = self.grid.get(row.checked_sub(1)?)?;
+        Some(line.iter().map(Cell::character).collect::<String>().trim_end().to_string())
+    }
+
+    /// Returns the characters of every line without trailing spaces, separated by LF.
+    pub fn text(&self) -> String {
+        (1..=self.rows)
+            .filter_map(|row| self.row_text(row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Interprets `input`.
+    ///
+    /// A sequence left incomplete at the end of `input` is interpreted once completed by the next input.
+    pub fn feed(&mut self, input: &str) {
+        let mut pending = core::mem::take(&mut self.pending);
+        pending.extend_from_slice(input.as_bytes());
+        self.consume(pending);
+    }
+
+    fn consume(&mut self, mut bytes: Vec<u8>) {
+        let (input, incomplete) = match core::str::from_utf8(&bytes) {
+            Ok(_) => (String::from_utf8(bytes).unwrap_or_default(), vec![]),
+            Err(e) if e.error_len().is_none() => {
+                let incomplete = bytes.split_off(e.valid_up_to());
+                (String::from_utf8(bytes).unwrap_or_default(), incomplete)
+            }
+            // Invalid bytes are replaced, as a terminal would print a substitute character.
+            Err(_) => (String::from_utf8_lossy(&bytes).into_owned(), vec![]),
+        };
+
+        let mut offset = 0;
+        while offset < input.len() {
+            let rest = &input[offset..];
+            let Some((kind, len)) = classify(rest) else {
+                let c = rest.chars().next().unwrap_or(' ');
+                self.print(c);
+                offset += c.len_utf8();
+                continue;
+            };
+            if len == rest.len() && validate(rest).is_err_and(|errors| errors.contains(&ValidationError::Unterminated(0))) {
+                break;
+            }
+            let sequence = &rest[..len];
+            match kind {
+                SequenceKind::Control => self.control(sequence.chars().next().unwrap_or(' ')),
+                SequenceKind::Csi => {
+                    if let Some((parameters, end, _)) = csi_parts(sequence) {
+                        self.control_sequence(parameters, end);
+                    }
+                }
+                SequenceKind::Escape => self.escape(&sequence[1..]),
+                _ => {}
+            }
+            offset += len;
+        }
+
+        self.pending = input.as_bytes()[offset..].to_vec();
+        self.pending.extend(incomplete);
+    }
+
+    fn print(&mut self, c: char) {
+        if self.cursor.1 >= self.columns {
+            self.cursor.1 = 0;
+            self.line_feed();
+        }
+        self.grid[self.cursor.0][self.cursor.1] = Cell { character: Some(c), style: self.style.clone() };
+        self.cursor.1 += 1;
+    }
+
+    fn control(&mut self, c: char) {
+        match c {
+            BS => self.cursor.1 = self.cursor.1.min(self.columns - 1).saturating_sub(1),
+            HT => self.cursor.1 = ((self.cursor.1 / 8 + 1) * 8).min(self.columns - 1),
+            LF | VT | FF | '\u{84}' => self.line_feed(),
+            CR => self.cursor.1 = 0,
+            '\u{85}' => {
+                self.cursor.1 = 0;
+                self.line_feed();
+            }
+            '\u{8D}' => self.reverse_line_feed(),
+            _ => {}
+        }
+    }
+
+    fn escape(&mut self, sequence: &str) {
+        match sequence {
+            "7" => self.saved = Some((self.cursor, self.style.clone())),
+            "8" => {
+                let (cursor, style) = self.saved.clone().unwrap_or_default();
+                self.cursor = cursor;
+                self.style = style;
+            }
+            "D" => self.line_feed(),
+            "E" => {
+                self.cursor.1 = 0;
+                self.line_feed();
+            }
+            "M" => self.reverse_line_feed(),
+            "c" => *self = Self::new(self.rows, self.columns),
+            _ => {}
+        }
+    }
+
+    fn control_sequence(&mut self, parameters: &str, end: &str) {
+        if parameters.starts_with(|c: char| ('\x3C'..='\x3F').contains(&c)) {
+            // Private parameters, not interpreted.
+            return;
+        }
+        let values = parameters.split(';')
+            .map(|value| value.parse::<usize>().ok())
+            .collect::<Vec<_>>();
+        let at = |i: usize, default: usize| values.get(i).copied().flatten().filter(|v| *v != 0).unwrap_or(default);
+        let n = at(0, 1);
+        let (row, column) = (self.cursor.0, self.cursor.1.min(self.columns - 1));
+
+        match end {
+            "A" => self.cursor = (row.saturating_sub(n), column),
+            "B" => self.cursor = ((row + n).min(self.rows - 1), column),
+            "C" => self.cursor = (row, (column + n).min(self.columns - 1)),
+            "D" => self.cursor = (row, column.saturating_sub(n)),
+            "E" => self.cursor = ((row + n).min(self.rows - 1), 0),
+            "F" => self.cursor = (row.saturating_sub(n), 0),
+            "G" | "`" => self.cursor = (row, (n - 1).min(self.columns - 1)),
+            "d" => self.cursor = ((n - 1).min(self.rows - 1), column),
+            "H" | "f" => self.cursor = ((n - 1).min(self.rows - 1), (at(1, 1) - 1).min(self.columns - 1)),
+            "J" => match values[0].unwrap_or(0) {
+                0 => {
+                    self.erase(row, column..self.columns);
+                    (row + 1..self.rows).for_each(|r| self.erase(r, 0..self.columns));
+                }
+                1 => {
+                    (0..row).for_each(|r| self.erase(r, 0..self.columns));
+                    self.erase(row, 0..column + 1);
+                }
+                2 => (0..self.rows).for_each(|r| self.erase(r, 0..self.columns)),
+                _ => {}
+            },
+            "K" => match values[0].unwrap_or(0) {
+                0 => self.erase(row, column..self.columns),
+                1 => self.erase(row, 0..column + 1),
+                2 => self.erase(row, 0..self.columns),
+                _ => {}
+            },
+            "@" => {
+                let line = &mut self.grid[row];
+                for _ in 0..n.min(self.columns - column) {
+                    line.insert(column, Cell::blank());
+                    line.pop();
+                }
+            }
+            "P" => {
+                let line = &mut self.grid[row];
+                for _ in 0..n.min(self.columns - column) {
+                    line.remove(column);
+                    line.push(Cell::blank());
+                }
+            }
+            "X" => self.erase(row, column..(column + n).min(self.columns)),
+            "L" if (self.region.0..=self.region.1).contains(&row) => self.scroll_down(row, n),
+            "M" if (self.region.0..=self.region.1).contains(&row) => self.scroll_up(row, n),
+            "S" => self.scroll_up(self.region.0, n),
+            "T" => self.scroll_down(self.region.0, n),
+            "m" => {
+                let codes = parameters.split(';')
+                    .map(|code| code.parse().unwrap_or(0))
+                    .collect::<Vec<_>>();
+                self.style.apply(&codes);
+            }
+            "r" => {
+                let (top, bottom) = (at(0, 1) - 1, at(1, self.rows).min(self.rows) - 1);
+                if top < bottom {
+                    self.region = (top, bottom);
+                    self.cursor = (0, 0);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn erase(&mut self, row: usize, columns: core::ops::Range<usize>) {
+        self.grid[row][columns].fill(Cell::blank());
+    }
+
+    fn line_feed(&mut self) {
+        if self.cursor.0 == self.region.1 {
+            self.scroll_up(self.region.0, 1);
+        } else if self.cursor.0 < self.rows - 1 {
+            self.cursor.0 += 1;
+        }
+    }
+
+    fn reverse_line_feed(&mut self) {
+        if self.cursor.0 == self.region.0 {
+            self.scroll_down(self.region.0, 1);
+        } else {
+            self.cursor.0 = self.cursor.0.saturating_sub(1);
+        }
+    }
+
+    /// Moves the lines from `top` to the bottom of the scrolling region up by `n` lines.
+    fn scroll_up(&mut self, top: usize, n: usize) {
+        for _ in 0..n.min(self.region.1 + 1 - top) {
+            self.grid.remove(top);
+            self.grid.insert(self.region.1, vec![Cell::blank(); self.columns]);
+        }
+    }
+
+    /// Moves the lines from `top` to the bottom of the scrolling region down by `n` lines.
+    fn scroll_down(&mut self, top: usize, n: usize) {
+        for _ in 0..n.min(self.region.1 + 1 - top) {
+            self.grid.remove(self.region.1);
+            self.grid.insert(top, vec![Cell::blank(); self.columns]);
+        }
+    }
+}
+
+impl Write for TestTerminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut pending = core::mem::take(&mut self.pending);
+        pending.extend_from_slice(buf);
+        self.consume(pending);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use crate::cursor::{set_position, DECRC, DECSC};
+    use crate::display::{scroll, ScrollDirection};
+    use crate::editor::{delete_char, erase_in_line, AreaPosition};
+    use crate::presentation::{format_str, select_graphic};
+    use crate::terminal::{TerminalModel, TestTerminal};
+    use crate::widgets::status_line;
+
+    #[test]
+    fn styled_cells() {
+        let mut terminal = TestTerminal::new(3, 10);
+        terminal.feed(&format!("{}{}b", set_position(2, 3), format_str("X", select_graphic().fg_red().bold())));
+
+        let cell = terminal.cell(2, 3).unwrap();
+        assert_eq!(cell.character(), 'X');
+        assert_eq!(cell.codes(), vec![1, 31]);
+        assert_eq!(cell.style().to_string(), "\x1b[1;31m");
+        assert_eq!(terminal.cell(2, 4).unwrap().codes(), vec![]);
+        assert_eq!(terminal.cursor(), (2, 5));
+        assert_eq!(terminal.text(), "\n  Xb\n");
+        assert!(terminal.cell(4, 1).is_none());
+    }
+
+    #[test]
+    fn model_current_style() {
+        let mut model = TerminalModel::new(2, 10);
+        model.write_all(format!("{}X", set_position(1, 1)).as_bytes()).unwrap();
+        model.write_all(select_graphic().fg_red().to_string().as_bytes()).unwrap();
+        assert_eq!(model.current_style().to_string(), "\x1b[31m");
+        model.feed("Y\x1b[0m");
+        assert_eq!(model.cell(1, 2).unwrap().codes(), vec![31]);
+        assert_eq!(model.cell(1, 1).unwrap().codes(), vec![]);
+        assert!(model.current_style().modes().is_empty());
+    }
+
+    #[test]
+    fn editing_and_wrapping() {
+        let mut terminal = TestTerminal::new(2, 5);
+        terminal.feed("abcdefg\r");
+        assert_eq!(terminal.text(), "abcde\nfg");
+
+        terminal.feed(&format!("{}{}", delete_char(1), erase_in_line(AreaPosition::AfterCursor)));
+        terminal.feed(&format!("{}{}", set_position(1, 2), delete_char(2)));
+        assert_eq!(terminal.text(), "ade\n");
+
+        terminal.feed(&status_line(2, "ok", select_graphic().negative(), 4));
+        assert_eq!(terminal.text(), "ade\nok");
+        assert_eq!(terminal.cell(2, 4).unwrap().codes(), vec![7]);
+        assert_eq!(terminal.cursor(), (1, 2));
+    }
+
+    #[test]
+    fn scrolling_region() {
+        let mut terminal = TestTerminal::new(4, 3);
+        terminal.feed("1\r\n2\r\n3\r\n4\x1b[2;3r");
+        assert_eq!(terminal.scroll_region(), (2, 3));
+        assert_eq!(terminal.cursor(), (1, 1));
+
+        terminal.feed(&format!("{}\n", set_position(3, 1)));
+        assert_eq!(terminal.text(), "1\n3\n\n4");
+        terminal.feed(&scroll(1, ScrollDirection::Down).to_string());
+        assert_eq!(terminal.text(), "1\n\n3\n4");
+    }
+
+    #[test]
+    fn split_writes() {
+        let mut terminal = TestTerminal::new(1, 5);
+        let output = format!("{}é{}{}a", DECSC, select_graphic().underline(), DECRC);
+        for byte in output.as_bytes() {
+            terminal.write_all(&[*byte]).unwrap();
+        }
+        assert_eq!(terminal.text(), "a");
+        assert_eq!(terminal.cell(1, 1).unwrap().codes(), vec![]);
+    }
+}
@@ -0,0 +1,436 @@
+//! This module provides helpers to measure and lay out text containing control functions.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use crate::format::{CR, FF, HT, LF, VT};
+use crate::parser::{classify, csi_parts, sequence_len, SequenceKind};
+use crate::presentation::{format_str, select_graphic, GraphicSelection};
+use crate::sgr::{selection, SgrState};
+
+/// Returns the number of columns `input` occupies once printed.
+///
+/// Control functions are skipped and every other character counts as one column : wide characters (like
+/// CJK ideographs or emoji) and combining characters are not handled.
+///
+/// ### Example
+/// ```
+/// use coded_chars::display_width;
+/// use coded_chars::presentation::{format_str, select_graphic};
+///
+/// assert_eq!(display_width(&format_str("World", select_graphic().fg_red().bold())), 5);
+/// ```
+pub fn display_width(input: &str) -> usize {
+    let mut width = 0;
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        let len = sequence_len(rest).unwrap_or_else(|| {
+            width += 1;
+            c.len_utf8()
+        });
+        rest = &rest[len..];
+    }
+    width
+}
+
+/// Removes the control functions from `input`, leaving its printable text.
+///
+/// Control sequences, escape sequences and control strings are removed, along with their parameters. The
+/// control characters are removed too, except the format effectors HT, LF, VT, FF and CR which keep the
+/// layout of the text.
+///
+/// ### Example
+/// ```
+/// use coded_chars::cursor::set_position;
+/// use coded_chars::presentation::{format_str, select_graphic};
+/// use coded_chars::strip_sequences;
+///
+/// let output = format!("{}Hello {}\x07", set_position(2, 1), format_str("World", select_graphic().bold()));
+/// assert_eq!(strip_sequences(&output), "Hello World");
+/// ```
+pub fn strip_sequences(input: &str) -> String {
+    let mut stripped = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        let len = match classify(rest) {
+            Some((SequenceKind::Control, len)) => {
+                if [HT, LF, VT, FF, CR].contains(&c) {
+                    stripped.push(c);
+                }
+                len
+            }
+            Some((_, len)) => len,
+            None => {
+                stripped.push(c);
+                c.len_utf8()
+            }
+        };
+        rest = &rest[len..];
+    }
+    stripped
+}
+
+/// Pads `input` with spaces on the right so that it occupies `width` columns.
+///
+/// A string already wider than `width` is returned unchanged.
+pub fn pad(input: &str, width: usize) -> String {
+    format!("{}{}", input, " ".repeat(width.saturating_sub(display_width(input))))
+}
+
+/// Extracts the part of `input` printed in the columns `start..end`.
+///
+/// The graphic rendition (SGR) in effect at `start` is re-applied at the beginning of the slice, and a
+/// default rendition is appended at its end if any rendition was applied. Other control functions are kept
+/// only inside the slice.
+///
+/// Wide characters (East Asian Wide and Fullwidth, like CJK ideographs, and emoji) count as two columns. A
+/// wide character straddling a bound of the slice is replaced by a space for each of its columns inside the
+/// slice, so the slice spans exactly `end - start` columns when `input` reaches `end`.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::{format_str, select_graphic};
+/// use coded_chars::text::slice_columns;
+///
+/// let line = format!("Hello {}", format_str("World", select_graphic().fg_red()));
+/// assert_eq!(slice_columns(&line, 8, 10), "\x1b[31mrl\x1b[0m");
+/// ```
+pub fn slice_columns(input: &str, start: usize, end: usize) -> String {
+    let mut slice = String::new();
+    let mut rendition = String::new();
+    let mut started = false;
+    let mut styled = false;
+    let mut column = 0;
+    let mut rest = input;
+
+    while let Some(c) = rest.chars().next() {
+        if column >= end {
+            break;
+        }
+        let len = match classify(rest) {
+            Some((kind, len)) => {
+                let sequence = &rest[..len];
+                let reset = match csi_parts(sequence) {
+                    Some((parameters, "m", _)) if kind == SequenceKind::Csi => Some(parameters.is_empty() || parameters == "0"),
+                    _ => None,
+                };
+                if started {
+                    slice.push_str(sequence);
+                    styled = reset.map_or(styled, |reset| !reset);
+                } else if let Some(reset) = reset {
+                    if reset {
+                        rendition.clear();
+                    } else {
+                        rendition.push_str(sequence);
+                    }
+                }
+                len
+            }
+            None => {
+                let width = char_width(c);
+                let (from, to) = (column.max(start), (column + width).min(end));
+                if from < to {
+                    if !started {
+                        started = true;
+                        styled = !rendition.is_empty();
+                        slice.push_str(&rendition);
+                    }
+                    if to - from == width {
+                        slice.push(c);
+                    } else {
+                        slice.push_str(&" ".repeat(to - from));
+                    }
+                }
+                column += width;
+                c.len_utf8()
+            }
+        };
+        rest = &rest[len..];
+    }
+
+    if styled {
+        slice.push_str(&select_graphic().default_rendition().to_string());
+    }
+    slice
+}
+
+/// Returns the number of columns of the printable character `c` : 2 for the East Asian Wide and Fullwidth
+/// characters and the emoji, 1 otherwise.
+fn char_width(c: char) -> usize {
+    match c {
+        '\u{1100}'..='\u{115F}'
+        | '\u{2E80}'..='\u{303E}'
+        | '\u{3041}'..='\u{33FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{A000}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}'
+        | '\u{1F300}'..='\u{1F64F}'
+        | '\u{1F900}'..='\u{1F9FF}'
+        | '\u{20000}'..='\u{2FFFD}'
+        | '\u{30000}'..='\u{3FFFD}' => 2,
+        _ => 1,
+    }
+}
+
+/// Replaces each character tabulation (HT) in `text` by spaces up to the next multiple of `tab_width` columns.
+///
+/// Control functions do not count in the column position, which is reset by CR and LF. With a `tab_width`
+/// of 0, tabulations are removed.
+///
+/// ### Example
+/// ```
+/// use coded_chars::text::expand_tabs;
+///
+/// assert_eq!(expand_tabs("a\tb", 4), "a   b");
+/// assert_eq!(expand_tabs("a\tbc\td", 4), "a   bc  d");
+/// ```
+pub fn expand_tabs(text: &str, tab_width: usize) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let len = match classify(rest) {
+            Some((SequenceKind::Control, len)) => {
+                match c {
+                    HT if tab_width > 0 => {
+                        let spaces = tab_width - column % tab_width;
+                        expanded.push_str(&" ".repeat(spaces));
+                        column += spaces;
+                    }
+                    HT => {}
+                    LF | CR => {
+                        expanded.push(c);
+                        column = 0;
+                    }
+                    _ => expanded.push(c),
+                }
+                len
+            }
+            Some((_, len)) => {
+                expanded.push_str(&rest[..len]);
+                len
+            }
+            None => {
+                expanded.push(c);
+                column += 1;
+                c.len_utf8()
+            }
+        };
+        rest = &rest[len..];
+    }
+    expanded
+}
+
+/// Returns the number of rows `input` occupies once printed on a terminal `term_width` columns wide.
+///
+/// Each line, ended by LF, takes at least one row and wraps automatically after `term_width` columns, its
+/// width being computed by [display_width]. A trailing LF counts the row where the cursor ends. With a
+/// `term_width` of 0, lines do not wrap.
+///
+/// Tabulations count as one column, see [expand_tabs].
+///
+/// ### Example
+/// ```
+/// use coded_chars::text::count_rows;
+///
+/// assert_eq!(count_rows("> a long command line", 10), 3);
+/// assert_eq!(count_rows("first\nsecond", 80), 2);
+/// ```
+pub fn count_rows(input: &str, term_width: usize) -> usize {
+    input.split(LF)
+        .map(|line| match (display_width(line), term_width) {
+            (0, _) | (_, 0) => 1,
+            (width, term_width) => width.div_ceil(term_width),
+        })
+        .sum()
+}
+
+/// Returns the graphic rendition in effect at `byte_offset` in `input`.
+///
+/// Every SGR sequence ending before `byte_offset` is replayed, assuming the GRAPHIC RENDITION
+/// COMBINATION MODE (GRCM) is set to CUMULATIVE. The returned selection establishes the resulting
+/// rendition from the default rendition.
+///
+/// ### Example
+/// ```
+/// use coded_chars::text::style_at;
+///
+/// let input = "\x1b[1mHello \x1b[31mWorld\x1b[22m !";
+/// assert_eq!(style_at(input, 16).to_string(), "\x1b[1;31m");
+/// ```
+pub fn style_at(input: &str, byte_offset: usize) -> GraphicSelection {
+    let mut state = SgrState::default();
+    let mut offset = 0;
+
+    while offset < input.len() {
+        let rest = &input[offset..];
+        let len = match classify(rest) {
+            Some((SequenceKind::Csi, len)) => {
+                if offset + len > byte_offset {
+                    break;
+                }
+                if let Some((parameters, "m", _)) = csi_parts(rest) {
+                    let codes = parameters.split(';')
+                        .map(|code| code.parse().unwrap_or(0))
+                        .collect::<Vec<_>>();
+                    state.apply(&codes);
+                }
+                len
+            }
+            Some((_, len)) => len,
+            None => rest.chars().next().map_or(1, char::len_utf8),
+        };
+        offset += len;
+    }
+    selection(&state.codes())
+}
+
+/// Wraps each occurrence of `needle` in `text` with `style` and a reset, see [format_str].
+///
+/// Occurrences are only searched in the text between control functions, which are copied untouched. After
+/// each occurrence, the rendition established by the preceding SGR sequences of `text` is restored. An
+/// empty `needle` matches nothing.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::select_graphic;
+/// use coded_chars::text::highlight_matches;
+///
+/// let line = highlight_matches("error: 2 errors", "error", select_graphic().fg_red());
+/// assert_eq!(line, "\x1b[31merror\x1b[0m: 2 \x1b[31merror\x1b[0ms");
+/// ```
+pub fn highlight_matches(text: &str, needle: &str, style: &GraphicSelection) -> String {
+    if needle.is_empty() {
+        return text.to_string();
+    }
+    let mut highlighted = String::with_capacity(text.len());
+    let mut state = SgrState::default();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let len = match classify(rest) {
+            Some((kind, len)) => {
+                if kind == SequenceKind::Csi {
+                    if let Some((parameters, "m", _)) = csi_parts(rest) {
+                        state.apply(&parameters.split(';').map(|code| code.parse().unwrap_or(0)).collect::<Vec<_>>());
+                    }
+                }
+                highlighted.push_str(&rest[..len]);
+                len
+            }
+            None if rest.starts_with(needle) => {
+                highlighted.push_str(&format_str(needle, style));
+                let codes = state.codes();
+                if !codes.is_empty() {
+                    highlighted.push_str(&selection(&codes).to_string());
+                }
+                needle.len()
+            }
+            None => {
+                let len = rest.chars().next().map_or(1, char::len_utf8);
+                highlighted.push_str(&rest[..len]);
+                len
+            }
+        };
+        rest = &rest[len..];
+    }
+    highlighted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::presentation::{format_str, select_graphic};
+    use crate::cursor::set_position;
+    use crate::text::{count_rows, display_width, expand_tabs, highlight_matches, slice_columns, strip_sequences, style_at};
+
+    #[test]
+    fn highlight_occurrences() {
+        let red = select_graphic().fg_red().clone();
+        assert_eq!(
+            highlight_matches("err: 2 err", "err", &red),
+            format!("{}: 2 {}", format_str("err", &red), format_str("err", &red))
+        );
+        assert_eq!(
+            highlight_matches("\x1b[1mAn err\x1b[0m \x1b[31m", "err", &red),
+            "\x1b[1mAn \x1b[31merr\x1b[0m\x1b[1m\x1b[0m \x1b[31m"
+        );
+        assert_eq!(highlight_matches("abc", "", &red), "abc");
+    }
+
+    #[test]
+    fn slice_colored_line() {
+        let line = format!("ab{}ef", format_str("cd", select_graphic().fg_red().bold()));
+        assert_eq!(slice_columns(&line, 0, 2), "ab");
+        assert_eq!(slice_columns(&line, 1, 3), "b\x1b[31;1mc\x1b[0m");
+        assert_eq!(slice_columns(&line, 3, 5), "\x1b[31;1md\x1b[0me");
+        assert_eq!(slice_columns(&line, 4, 10), "ef");
+        assert_eq!(slice_columns(&line, 10, 12), "");
+    }
+
+    #[test]
+    fn slice_wide_characters() {
+        let line = format!("a{}b", format_str("中文", select_graphic().fg_red()));
+        assert_eq!(slice_columns(&line, 1, 5), "\x1b[31m中文\x1b[0m");
+        assert_eq!(slice_columns(&line, 2, 5), "\x1b[31m 文\x1b[0m");
+        assert_eq!(slice_columns(&line, 0, 4), "a\x1b[31m中 \x1b[0m");
+        assert_eq!(slice_columns(&line, 2, 4), "\x1b[31m  \x1b[0m");
+        assert_eq!(slice_columns(&line, 4, 6), "\x1b[31m \x1b[0mb");
+        assert_eq!(slice_columns("🙂🙂", 1, 3), "  ");
+    }
+
+    #[test]
+    fn expand_tabs_with_colors() {
+        let red = select_graphic().fg_red().clone();
+        let text = format!("{}\tb\nab\tc", format_str("a", &red));
+        assert_eq!(expand_tabs(&text, 4), format!("{}   b\nab  c", format_str("a", &red)));
+        assert_eq!(expand_tabs("a\tb", 4), "a   b");
+        assert_eq!(expand_tabs("\tx\r\ty", 4), "    x\r    y");
+        assert_eq!(expand_tabs("abcd\te", 4), "abcd    e");
+        assert_eq!(expand_tabs("a\tb", 0), "ab");
+    }
+
+    #[test]
+    fn visible_width() {
+        assert_eq!(display_width(&format_str("World", select_graphic().fg_red().bold())), 5);
+        assert_eq!(display_width(&format!("{}é{}", set_position(3, 4), select_graphic().fg_color_256(208))), 1);
+        assert_eq!(display_width(""), 0);
+    }
+
+    #[test]
+    fn strip_mixed_output() {
+        let output = format!(
+            "{}{}: {}\r\n{}\x1b]0;title\x07done\x1b[?25h",
+            set_position(1, 1),
+            format_str("error", select_graphic().fg_red().bold()),
+            format_str("42", select_graphic().fg_rgb(255, 128, 0)),
+            set_position(12, 40),
+        );
+        assert_eq!(strip_sequences(&output), "error: 42\r\ndone");
+        assert_eq!(strip_sequences("\x00a\x1b7b\x1b(Bc\x7f\u{9b}1md"), "abcd");
+    }
+
+    #[test]
+    fn style_at_offset() {
+        let input = format!("a{}b{}c{}d", select_graphic().bold(), select_graphic().fg_index16(208), select_graphic().not_bold_or_faint());
+        assert_eq!(style_at(&input, 0).to_string(), "\x1b[m");
+        assert_eq!(style_at(&input, 6).to_string(), "\x1b[1m");
+        assert_eq!(style_at(&input, 20).to_string(), "\x1b[1;38;5;208m");
+        assert_eq!(style_at(&input, input.len()).to_string(), "\x1b[38;5;208m");
+    }
+
+    #[test]
+    fn wrapped_rows() {
+        assert_eq!(count_rows(&"x".repeat(25), 10), 3);
+        assert_eq!(count_rows(&"x".repeat(20), 10), 2);
+        assert_eq!(count_rows(&format_str(&"x".repeat(10), select_graphic().bold()), 10), 1);
+        assert_eq!(count_rows("ab\n\ncd\n", 10), 4);
+        assert_eq!(count_rows("", 10), 1);
+        assert_eq!(count_rows(&"x".repeat(25), 0), 1);
+    }
+}
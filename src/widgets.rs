@@ -0,0 +1,240 @@
+//! This module assembles control functions into common text-based interface elements.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use crate::cursor::{set_position, DECRC, DECSC};
+use crate::editor::{erase_in_line, AreaPosition};
+use crate::format::{CR, LF};
+use crate::display::vertical_rule;
+use crate::presentation::{format_str, repeat_char, GraphicSelection};
+use crate::text::{display_width, pad, slice_columns};
+
+/// Renders a table row, each cell being styled and padded to the width of its column.
+///
+/// Cells are separated by a space. A cell wider than its column is not truncated, and cells without a
+/// matching width are not padded.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::select_graphic;
+/// use coded_chars::widgets::table_row;
+///
+/// println!("{}", table_row(&["Name", "Size"], &[10, 6], select_graphic().bold()));
+/// println!("{}", table_row(&["Cargo.toml", "1 kB"], &[10, 6], &select_graphic()));
+/// ```
+pub fn table_row(cells: &[&str], widths: &[usize], style: &GraphicSelection) -> String {
+    styled_table_row(&cells.iter().map(|cell| (*cell, style)).collect::<Vec<_>>(), widths)
+}
+
+/// Same as [table_row], with a style for each cell.
+///
+/// Each cell is followed by a reset, so the style of a cell never leaks into the next one.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::select_graphic;
+/// use coded_chars::widgets::styled_table_row;
+///
+/// let plain = select_graphic();
+/// println!("{}", styled_table_row(&[("Cargo.toml", &plain), ("error", select_graphic().fg_red())], &[10, 6]));
+/// ```
+pub fn styled_table_row(cells: &[(&str, &GraphicSelection)], widths: &[usize]) -> String {
+    cells.iter()
+        .enumerate()
+        .map(|(i, (cell, style))| format_str(&pad(cell, widths.get(i).copied().unwrap_or(0)), style))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Renders a status line on the line `row`, leaving the cursor where it was.
+///
+/// The cursor is saved ([DECSC]) and moved to the first column of `row`, the line is erased, then `content` is
+/// printed with `style` and padded to `width` columns, and the cursor is restored ([DECRC]).
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::select_graphic;
+/// use coded_chars::widgets::status_line;
+///
+/// print!("{}", status_line(24, "Ready", select_graphic().negative(), 80));
+/// ```
+pub fn status_line(row: usize, content: &str, style: &GraphicSelection, width: usize) -> String {
+    format!(
+        "{}{}{}{}{}",
+        DECSC,
+        set_position(row, 1),
+        erase_in_line(AreaPosition::Whole),
+        format_str(&pad(content, width), style),
+        DECRC
+    )
+}
+
+/// Renders a box of `width` columns and `height` lines, borders included, with `title` inset into its top
+/// border.
+///
+/// The border is drawn with box-drawing characters printed with `border_style`, the title is printed as is
+/// and truncated to fit in the top border. Lines are separated by CR LF, so each line starts at the first
+/// column. A box is at least 2 columns wide and 2 lines high.
+///
+/// ### Example
+/// ```
+/// use coded_chars::presentation::select_graphic;
+/// use coded_chars::widgets::titled_box;
+///
+/// println!("{}", titled_box("Files", 20, 5, select_graphic().fg_blue()));
+/// ```
+pub fn titled_box(title: &str, width: usize, height: usize, border_style: &GraphicSelection) -> String {
+    let inner = width.max(2) - 2;
+    let title = if display_width(title) < inner { title.to_string() } else { slice_columns(title, 0, inner.saturating_sub(1)) };
+    let filler = inner.saturating_sub(display_width(&title) + 1);
+
+    let top = if inner == 0 {
+        format_str("┌┐", border_style)
+    } else {
+        format!(
+            "{}{}{}",
+            format_str("┌─", border_style),
+            title,
+            format_str(&format!("{}┐", "─".repeat(filler)), border_style)
+        )
+    };
+    let side = format_str("│", border_style);
+    let middle = format!("{}{}{}", side, " ".repeat(inner), side);
+    let bottom = format_str(&format!("└{}┘", "─".repeat(inner)), border_style);
+
+    let mut lines = vec![top];
+    lines.resize(height.max(2) - 1, middle);
+    lines.push(bottom);
+    lines.join(&format!("{}{}", CR, LF))
+}
+
+/// The glyphs of the border drawn by [box_rect].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BoxStyle {
+    /// `+`, `-` and `|`, printable by any terminal.
+    Ascii,
+    /// `┌`, `┐`, `└`, `┘`, `─` and `│`.
+    Unicode,
+}
+
+impl BoxStyle {
+    /// Returns the top-left, top-right, bottom-left and bottom-right corners.
+    pub fn corners(&self) -> [char; 4] {
+        match self {
+            BoxStyle::Ascii => ['+', '+', '+', '+'],
+            BoxStyle::Unicode => ['┌', '┐', '└', '┘'],
+        }
+    }
+
+    /// Returns the character of the top and bottom borders.
+    pub fn horizontal(&self) -> char {
+        match self {
+            BoxStyle::Ascii => '-',
+            BoxStyle::Unicode => '─',
+        }
+    }
+
+    /// Returns the character of the left and right borders.
+    pub fn vertical(&self) -> char {
+        match self {
+            BoxStyle::Ascii => '|',
+            BoxStyle::Unicode => '│',
+        }
+    }
+}
+
+/// Renders the border of a box of `width` columns and `height` lines, from line `top` and column `left`.
+///
+/// Each border is reached with CUP, the inside of the box is left untouched. A box is at least 2 columns
+/// wide and 2 lines high.
+///
+/// ### Example
+/// ```
+/// use coded_chars::widgets::{box_rect, BoxStyle};
+///
+/// print!("{}", box_rect(2, 4, 20, 5, BoxStyle::Unicode));
+/// ```
+pub fn box_rect(top: usize, left: usize, width: usize, height: usize, style: BoxStyle) -> String {
+    let (width, height) = (width.max(2), height.max(2));
+    let [top_left, top_right, bottom_left, bottom_right] = style.corners();
+    let border = |l: usize, first: char, last: char| format!(
+        "{}{}{}{}",
+        set_position(l, left),
+        first,
+        repeat_char(style.horizontal(), width - 2),
+        last
+    );
+
+    format!(
+        "{}{}{}{}",
+        border(top, top_left, top_right),
+        vertical_rule(top + 1, left, height - 2, style.vertical()),
+        vertical_rule(top + 1, left + width - 1, height - 2, style.vertical()),
+        border(top + height - 1, bottom_left, bottom_right)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::presentation::select_graphic;
+    use crate::text::{display_width, strip_sequences};
+    use crate::widgets::{box_rect, status_line, styled_table_row, table_row, titled_box, BoxStyle};
+
+    #[test]
+    fn table_row_alignment() {
+        let style = select_graphic().fg_red().clone();
+        assert_eq!(
+            table_row(&["ab", "c"], &[4, 3], &style),
+            "\x1b[31mab  \x1b[0m \x1b[31mc  \x1b[0m"
+        );
+        assert_eq!(
+            table_row(&["abcd", "\x1b[1mc\x1b[0m"], &[4, 3], &style),
+            "\x1b[31mabcd\x1b[0m \x1b[31m\x1b[1mc\x1b[0m  \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn styled_table_row_alignment() {
+        let plain = select_graphic();
+        let red = select_graphic().fg_red().clone();
+        let row = styled_table_row(&[("name", &plain), ("ok", &red)], &[6, 4]);
+        assert_eq!(row, "\x1b[mname  \x1b[0m \x1b[31mok  \x1b[0m");
+        assert_eq!(strip_sequences(&row), "name   ok  ");
+        assert_eq!(display_width(&row), 11);
+    }
+
+    #[test]
+    fn box_rect_placement() {
+        assert_eq!(
+            box_rect(2, 5, 3, 3, BoxStyle::Ascii),
+            "\x1b[2;5H+-+\x1b[3;5H|\x1b[3;7H|\x1b[4;5H+-+"
+        );
+        assert_eq!(
+            box_rect(1, 1, 3, 3, BoxStyle::Unicode),
+            "\x1b[1;1H┌─┐\x1b[2;1H│\x1b[2;3H│\x1b[3;1H└─┘"
+        );
+        assert_eq!(box_rect(1, 1, 0, 0, BoxStyle::Ascii), "\x1b[1;1H++\x1b[2;1H++");
+    }
+
+    #[test]
+    fn status_line_order() {
+        assert_eq!(
+            status_line(24, "Ready", select_graphic().negative(), 8),
+            "\x1b7\x1b[24;1H\x1b[2K\x1b[7mReady   \x1b[0m\x1b8"
+        );
+    }
+
+    #[test]
+    fn titled_box_top_row() {
+        let style = select_graphic().fg_blue().clone();
+        let lines = titled_box("Files", 10, 3, &style).split("\r\n").map(String::from).collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "\x1b[34m┌─\x1b[0mFiles\x1b[34m──┐\x1b[0m");
+        assert_eq!(lines[1], "\x1b[34m│\x1b[0m        \x1b[34m│\x1b[0m");
+        assert_eq!(lines[2], "\x1b[34m└────────┘\x1b[0m");
+
+        let truncated = titled_box("Long title", 6, 2, &style);
+        assert!(truncated.starts_with("\x1b[34m┌─\x1b[0mLon\x1b[34m┐\x1b[0m\r\n"));
+    }
+}
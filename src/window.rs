@@ -0,0 +1,125 @@
+//! This module helps manipulate the terminal window.
+//!
+//! The window manipulation sequence (`CSI Ps ; Ps ; Ps t`) is a DEC and xterm extension widely implemented by
+//! terminals, it is not part of ECMA-48. Many terminals disable some operations for security reasons.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use crate::control::{parse_numbers, ControlSequence, ParseError};
+
+/// The window operations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WindowOp {
+    /// De-iconifies the window.
+    Deiconify,
+    /// Iconifies the window.
+    Iconify,
+    /// Moves the window to the pixel position `x ; y`.
+    Move,
+    /// Resizes the window to `height ; width` pixels.
+    ResizePixels,
+    /// Raises the window to the front.
+    Raise,
+    /// Lowers the window to the bottom.
+    Lower,
+    /// Refreshes the window.
+    Refresh,
+    /// Resizes the text area to `rows ; columns` characters.
+    Resize,
+    /// Requests the state of the window, iconified or not.
+    ReportState,
+    /// Requests the position of the window, in pixels.
+    ReportPosition,
+    /// Requests the size of the text area, in pixels.
+    ReportSizePixels,
+    /// Requests the size of the text area, in characters, see [parse_size_report].
+    ReportSize,
+    /// Requests the size of the screen, in characters.
+    ReportScreenSize,
+}
+
+impl Display for WindowOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", match self {
+            WindowOp::Deiconify => "1",
+            WindowOp::Iconify => "2",
+            WindowOp::Move => "3",
+            WindowOp::ResizePixels => "4",
+            WindowOp::Raise => "5",
+            WindowOp::Lower => "6",
+            WindowOp::Refresh => "7",
+            WindowOp::Resize => "8",
+            WindowOp::ReportState => "11",
+            WindowOp::ReportPosition => "13",
+            WindowOp::ReportSizePixels => "14",
+            WindowOp::ReportSize => "18",
+            WindowOp::ReportScreenSize => "19",
+        })
+    }
+}
+
+/// Applies the window operation `op`, with the arguments `args`.
+///
+/// ### Example
+/// ```
+/// use coded_chars::window::{manipulate, WindowOp};
+///
+/// assert_eq!(manipulate(WindowOp::Resize, &[24, 80]).to_string(), "\x1b[8;24;80t");
+/// assert_eq!(manipulate(WindowOp::ReportSize, &[]).to_string(), "\x1b[18t");
+/// ```
+pub fn manipulate(op: WindowOp, args: &[usize]) -> ControlSequence {
+    let parameters = core::iter::once(op.to_string())
+        .chain(args.iter().map(usize::to_string))
+        .collect::<Vec<_>>();
+    ControlSequence::new(&parameters.iter().map(|s| s.as_str()).collect::<Vec<_>>(), "t")
+}
+
+/// The size of the text area, in characters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WindowSize {
+    pub rows: usize,
+    pub columns: usize,
+}
+
+/// Decodes the `CSI 8 ; rows ; columns t` reply sent by a terminal to [WindowOp::ReportSize].
+///
+/// Bytes following the reply are ignored.
+///
+/// ### Example
+/// ```
+/// use coded_chars::window::{parse_size_report, WindowSize};
+///
+/// assert_eq!(parse_size_report(b"\x1b[8;24;80t"), Ok(WindowSize { rows: 24, columns: 80 }));
+/// ```
+pub fn parse_size_report(bytes: &[u8]) -> Result<WindowSize, ParseError> {
+    let numbers = parse_numbers(bytes, b't', false)?;
+    match numbers[..] {
+        [(_, Some(8)), (_, Some(rows)), (_, Some(columns))] => Ok(WindowSize { rows, columns }),
+        [(offset, _), ..] => Err(ParseError::InvalidByte(offset)),
+        // The final byte directly follows the introducer.
+        [] => Err(ParseError::InvalidByte(if bytes[0] == 0x1B { 2 } else { 1 })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::control::ParseError;
+    use crate::window::{manipulate, parse_size_report, WindowOp, WindowSize};
+
+    #[test]
+    fn window_operations() {
+        assert_eq!(manipulate(WindowOp::ReportSize, &[]).to_string(), "\x1b[18t");
+        assert_eq!(manipulate(WindowOp::Raise, &[]).to_string(), "\x1b[5t");
+        assert_eq!(manipulate(WindowOp::Move, &[10, 20]).to_string(), "\x1b[3;10;20t");
+    }
+
+    #[test]
+    fn size_report() {
+        assert_eq!(parse_size_report(b"\x1b[8;24;80t"), Ok(WindowSize { rows: 24, columns: 80 }));
+        assert_eq!(parse_size_report(b"\x1b[4;480;640t"), Err(ParseError::InvalidByte(2)));
+        assert_eq!(parse_size_report(b"\x1b[8;24t"), Err(ParseError::InvalidByte(2)));
+        assert_eq!(parse_size_report(b"\x1b[t"), Err(ParseError::InvalidByte(2)));
+        assert_eq!(parse_size_report(b"\x1b[8;24;80"), Err(ParseError::Unterminated));
+    }
+}